@@ -1,5 +1,6 @@
 use anyhow::Result;
 use deadpool_postgres::{Manager, ManagerConfig, Pool, RecyclingMethod};
+use serde::Deserialize;
 use tokio_postgres::NoTls;
 use tracing::info;
 
@@ -12,6 +13,34 @@ pub struct HotAccount {
     pub max_priority_fee: Option<i64>,
     pub avg_contention: Option<f64>,
     pub max_contention: Option<f64>,
+    /// Reads of the account in slots where it was also written
+    pub read_conflicts: i64,
+    /// Share of the per-account block CU limit used, in percent
+    pub avg_cu_saturation: Option<f64>,
+    pub max_cu_saturation: Option<f64>,
+}
+
+/// Ranking of `get_hot_accounts`
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HotAccountSort {
+    /// Average writers per slot
+    #[default]
+    Contention,
+    /// Write lock attempts in the window
+    Transactions,
+    /// Average share of the per-account block CU limit
+    Saturation,
+}
+
+impl HotAccountSort {
+    fn order_by(self) -> &'static str {
+        match self {
+            HotAccountSort::Contention => "avg_contention DESC NULLS LAST, lock_attempts DESC",
+            HotAccountSort::Transactions => "lock_attempts DESC, avg_contention DESC NULLS LAST",
+            HotAccountSort::Saturation => "avg_cu_saturation DESC NULLS LAST, lock_attempts DESC",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -20,6 +49,9 @@ pub struct AccountStats {
     pub successful_locks: i64,
     pub failed_locks: i64,
     pub avg_contention: Option<f64>,
+    pub read_conflicts: i64,
+    pub avg_cu_saturation: Option<f64>,
+    pub max_cu_saturation: Option<f64>,
     pub avg_priority_fee: Option<f64>,
     pub max_priority_fee: Option<i64>,
 }
@@ -30,11 +62,219 @@ pub struct DashboardStats {
     pub total_events: i64,
     pub high_contention_accounts: i64,
     pub avg_success_rate: f64,
+    /// Average delay between block time and indexer ingestion, over blocks of the
+    /// last 5 minutes so backfilled and replayed history doesn't count
+    pub avg_ingest_lag_seconds: f64,
+    /// Transactions in the window's blocks, from slot summaries. Vote transactions
+    /// have no write lock events unless the indexer is told to include them.
+    pub vote_transactions: i64,
+    pub non_vote_transactions: i64,
+}
+
+/// Block-wide totals of one slot
+#[derive(Debug, Clone)]
+pub struct SlotSummary {
+    pub slot: i64,
+    pub block_time: chrono::DateTime<chrono::Utc>,
+    pub time_estimated: bool,
+    pub skipped: bool,
+    pub parent_slot: Option<i64>,
+    pub leader: Option<String>,
+    pub transaction_count: i32,
+    pub vote_transaction_count: i32,
+    pub failed_transaction_count: i32,
+    pub total_compute_units: i64,
+    pub total_fees_lamports: i64,
+    pub total_priority_fees_lamports: i64,
+    pub block_fullness_pct: f32,
+    /// From the slot's conflict graph, `None` for skipped slots
+    pub longest_chain: Option<i32>,
+    pub parallelism_factor: Option<f32>,
+    pub bottleneck_writers: Option<i32>,
+}
+
+const SLOT_SUMMARY_COLUMNS: &str = r#"
+    s.slot,
+    s.time as block_time,
+    s.time_estimated,
+    s.skipped,
+    s.parent_slot,
+    s.leader,
+    s.transaction_count,
+    s.vote_transaction_count,
+    s.failed_transaction_count,
+    s.total_compute_units,
+    s.total_fees_lamports,
+    s.total_priority_fees_lamports,
+    s.block_fullness_pct,
+    g.longest_chain,
+    g.parallelism_factor,
+    g.bottleneck_writers
+"#;
+
+impl SlotSummary {
+    fn from_row(row: &tokio_postgres::Row) -> Self {
+        Self {
+            slot: row.get("slot"),
+            block_time: row.get("block_time"),
+            time_estimated: row.get("time_estimated"),
+            skipped: row.get("skipped"),
+            parent_slot: row.get("parent_slot"),
+            leader: row.get("leader"),
+            transaction_count: row.get("transaction_count"),
+            vote_transaction_count: row.get("vote_transaction_count"),
+            failed_transaction_count: row.get("failed_transaction_count"),
+            total_compute_units: row.get("total_compute_units"),
+            total_fees_lamports: row.get("total_fees_lamports"),
+            total_priority_fees_lamports: row.get("total_priority_fees_lamports"),
+            block_fullness_pct: row.get("block_fullness_pct"),
+            longest_chain: row.get("longest_chain"),
+            parallelism_factor: row.get("parallelism_factor"),
+            bottleneck_writers: row.get("bottleneck_writers"),
+        }
+    }
+}
+
+/// Aggregates over the slots one validator was scheduled to produce
+#[derive(Debug, Clone)]
+pub struct LeaderStats {
+    pub leader: String,
+    pub slots: i64,
+    pub skipped_slots: i64,
+    pub avg_block_fullness_pct: Option<f64>,
+    pub avg_priority_fee: Option<f64>,
+    pub hot_lock_attempts: i64,
+    pub hot_successful_locks: i64,
+}
+
+/// Conflict graph metrics of one block
+#[derive(Debug, Clone)]
+pub struct SlotConflicts {
+    pub slot: i64,
+    pub transaction_count: i32,
+    pub component_count: i32,
+    pub largest_component: i32,
+    pub longest_chain: i32,
+    pub parallelism_factor: f32,
+    pub bottleneck_account: Option<String>,
+    pub bottleneck_writers: i32,
+    pub top_accounts: Vec<SerializingAccount>,
+}
+
+/// An account whose writers run one after another within a block
+#[derive(Debug, Clone)]
+pub struct SerializingAccount {
+    pub account_pubkey: String,
+    pub writers: i64,
+    pub compute_units: Option<i64>,
+}
+
+/// What `get_failure_breakdown` groups write lock events by
+#[derive(Debug, Clone, Copy)]
+pub enum FailureScope {
+    Account,
+    /// Program the writes are attributed to
+    Program,
+}
+
+impl FailureScope {
+    fn column(self) -> &'static str {
+        match self {
+            FailureScope::Account => "account_pubkey",
+            FailureScope::Program => "program_id",
+        }
+    }
+}
+
+/// Failed transactions of an account or program, by classified reason
+#[derive(Debug, Clone)]
+pub struct FailureBreakdown {
+    pub transactions: i64,
+    pub failed_transactions: i64,
+    pub reasons: Vec<FailureReasonCount>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FailureReasonCount {
+    /// `unclassified` for events indexed before reasons were recorded
+    pub reason: String,
+    /// Program-specific `Custom` error code
+    pub error_code: Option<i64>,
+    pub failures: i64,
+}
+
+/// Fee payers write-locking one account
+#[derive(Debug, Clone)]
+pub struct AccountSigners {
+    pub lock_attempts: i64,
+    pub unique_signers: i64,
+    /// Herfindahl index of the signers' shares of the lock attempts, from near 0
+    /// (many small senders) to 1 (a single sender)
+    pub hhi: f64,
+    pub top_signers: Vec<SignerActivity>,
+}
+
+/// One fee payer's write locks on an account
+#[derive(Debug, Clone)]
+pub struct SignerActivity {
+    pub fee_payer: String,
+    pub lock_attempts: i64,
+    pub successful_locks: i64,
+    pub avg_priority_fee: Option<f64>,
+    pub avg_tip: Option<f64>,
+}
+
+/// Fee-payer concentration of one hot account
+#[derive(Debug, Clone)]
+pub struct SignerConcentration {
+    pub account_pubkey: String,
+    pub lock_attempts: i64,
+    pub unique_signers: i64,
+    /// Share of the lock attempts sent by the busiest signer, 0 to 1
+    pub top_signer_share: f64,
+    pub hhi: f64,
+}
+
+/// Transactions of one fee payer across all accounts
+#[derive(Debug, Clone)]
+pub struct SignerStats {
+    pub transactions: i64,
+    pub successful_transactions: i64,
+    pub accounts_written: i64,
+    pub avg_priority_fee: Option<f64>,
+    pub avg_tip: Option<f64>,
+}
+
+/// Write contention and fees over all token accounts of one mint
+#[derive(Debug, Clone)]
+pub struct MintStats {
+    pub token_accounts: i64,
+    pub lock_attempts: i64,
+    pub successful_locks: i64,
+    pub avg_contention: Option<f64>,
+    pub max_contention: Option<f64>,
+    pub avg_priority_fee: Option<f64>,
+    pub max_priority_fee: Option<i64>,
+    pub avg_tip: Option<f64>,
+    pub top_accounts: Vec<MintAccount>,
 }
 
+/// A token account of the mint and its write contention
+#[derive(Debug, Clone)]
+pub struct MintAccount {
+    pub account_pubkey: String,
+    pub lock_attempts: i64,
+    pub avg_contention: Option<f64>,
+}
+
+/// Compute units all writers of one account may consume per block
+pub const DEFAULT_ACCOUNT_CU_LIMIT: i64 = 12_000_000;
+
 #[derive(Clone)]
 pub struct Database {
     pool: Pool,
+    /// Per-account block CU limit that saturation is measured against
+    account_cu_limit: i64,
 }
 
 impl Database {
@@ -51,7 +291,16 @@ impl Database {
 
         info!("Database connection pool created");
 
-        Ok(Self { pool })
+        Ok(Self { pool, account_cu_limit: DEFAULT_ACCOUNT_CU_LIMIT })
+    }
+
+    pub fn with_account_cu_limit(mut self, account_cu_limit: i64) -> Self {
+        self.account_cu_limit = account_cu_limit.max(1);
+        self
+    }
+
+    pub fn account_cu_limit(&self) -> i64 {
+        self.account_cu_limit
     }
 
     /// Get dashboard overview stats
@@ -64,19 +313,38 @@ impl Database {
                 COUNT(DISTINCT account_pubkey) as unique_accounts,
                 COUNT(*) as total_events,
                 COUNT(DISTINCT account_pubkey) FILTER (WHERE lock_contention_score >= 5) as high_contention_accounts,
-                (COUNT(*) FILTER (WHERE success)::float8 / NULLIF(COUNT(*)::float8, 0) * 100) as avg_success_rate
+                (COUNT(*) FILTER (WHERE success)::float8 / NULLIF(COUNT(*)::float8, 0) * 100) as avg_success_rate,
+                AVG(EXTRACT(EPOCH FROM (ingested_at - time)))
+                    FILTER (WHERE time >= NOW() - INTERVAL '5 minutes' AND NOT time_estimated)::float8
+                    as avg_ingest_lag_seconds
             FROM write_lock_events
             WHERE time >= NOW() - ($1::text || ' minutes')::INTERVAL
             "#,
             &[&minutes.to_string()],
         ).await?;
 
+        let votes = client.query_one(
+            r#"
+            SELECT
+                COALESCE(SUM(vote_transaction_count), 0)::int8 as vote_transactions,
+                COALESCE(SUM(transaction_count - vote_transaction_count), 0)::int8 as non_vote_transactions
+            FROM slot_summaries
+            WHERE time >= NOW() - ($1::text || ' minutes')::INTERVAL
+            "#,
+            &[&minutes.to_string()],
+        ).await?;
+        let vote_transactions = votes.get("vote_transactions");
+        let non_vote_transactions = votes.get("non_vote_transactions");
+
         if rows.is_empty() {
             return Ok(DashboardStats {
                 unique_accounts: 0,
                 total_events: 0,
                 high_contention_accounts: 0,
                 avg_success_rate: 0.0,
+                avg_ingest_lag_seconds: 0.0,
+                vote_transactions,
+                non_vote_transactions,
             });
         }
 
@@ -86,6 +354,9 @@ impl Database {
             total_events: row.get("total_events"),
             high_contention_accounts: row.get("high_contention_accounts"),
             avg_success_rate: row.get::<_, Option<f64>>("avg_success_rate").unwrap_or(0.0),
+            avg_ingest_lag_seconds: row.get::<_, Option<f64>>("avg_ingest_lag_seconds").unwrap_or(0.0),
+            vote_transactions,
+            non_vote_transactions,
         })
     }
 
@@ -95,11 +366,14 @@ impl Database {
         &self,
         limit: i64,
         minutes: i64,
+        sort: HotAccountSort,
     ) -> Result<Vec<HotAccount>> {
         let client = self.pool.get().await?;
         
+        // Each (slot, account) pair has exactly one queue_position = 1 row, so
+        // per-slot totals are aggregated over those rows only
         let rows = client.query(
-            r#"
+            &format!(r#"
             SELECT
                 time_bucket('5 minutes', time) as bucket,
                 account_pubkey,
@@ -108,14 +382,17 @@ impl Database {
                 AVG(priority_fee_lamports)::float8 as avg_priority_fee,
                 MAX(priority_fee_lamports) as max_priority_fee,
                 AVG(lock_contention_score)::float8 as avg_contention,
-                MAX(lock_contention_score)::float8 as max_contention
+                MAX(lock_contention_score)::float8 as max_contention,
+                COALESCE(SUM(read_conflicts) FILTER (WHERE queue_position = 1), 0)::int8 as read_conflicts,
+                AVG(account_compute_units) FILTER (WHERE queue_position = 1)::float8 / $3 * 100 as avg_cu_saturation,
+                MAX(account_compute_units)::float8 / $3 * 100 as max_cu_saturation
             FROM write_lock_events
             WHERE time >= NOW() - ($1::text || ' minutes')::INTERVAL
             GROUP BY bucket, account_pubkey
-            ORDER BY avg_contention DESC NULLS LAST, lock_attempts DESC
+            ORDER BY {}
             LIMIT $2
-            "#,
-            &[&minutes.to_string(), &limit],
+            "#, sort.order_by()),
+            &[&minutes.to_string(), &limit, &(self.account_cu_limit as f64)],
         ).await?;
 
         let accounts = rows.iter().map(|row| HotAccount {
@@ -126,6 +403,9 @@ impl Database {
             max_priority_fee: row.get("max_priority_fee"),
             avg_contention: row.get("avg_contention"),
             max_contention: row.get("max_contention"),
+            read_conflicts: row.get("read_conflicts"),
+            avg_cu_saturation: row.get("avg_cu_saturation"),
+            max_cu_saturation: row.get("max_cu_saturation"),
         }).collect();
 
         Ok(accounts)
@@ -146,13 +426,16 @@ impl Database {
                 COUNT(*) FILTER (WHERE success) as successful_locks,
                 COUNT(*) FILTER (WHERE NOT success) as failed_locks,
                 AVG(lock_contention_score)::float8 as avg_contention,
+                COALESCE(SUM(read_conflicts) FILTER (WHERE queue_position = 1), 0)::int8 as read_conflicts,
+                AVG(account_compute_units) FILTER (WHERE queue_position = 1)::float8 / $3 * 100 as avg_cu_saturation,
+                MAX(account_compute_units)::float8 / $3 * 100 as max_cu_saturation,
                 AVG(priority_fee_lamports)::float8 as avg_priority_fee,
                 MAX(priority_fee_lamports) as max_priority_fee
             FROM write_lock_events
             WHERE account_pubkey = $1
               AND time >= NOW() - ($2::text || ' hours')::INTERVAL
             "#,
-            &[&pubkey, &hours.to_string()],
+            &[&pubkey, &hours.to_string(), &(self.account_cu_limit as f64)],
         ).await?;
 
         if rows.is_empty() {
@@ -165,18 +448,436 @@ impl Database {
             successful_locks: row.get("successful_locks"),
             failed_locks: row.get("failed_locks"),
             avg_contention: row.get("avg_contention"),
+            read_conflicts: row.get("read_conflicts"),
+            avg_cu_saturation: row.get("avg_cu_saturation"),
+            max_cu_saturation: row.get("max_cu_saturation"),
             avg_priority_fee: row.get("avg_priority_fee"),
             max_priority_fee: row.get("max_priority_fee"),
         }))
     }
 
+    /// Get the most recent slot summaries, newest first, optionally only slots before `before`
+    pub async fn get_slot_summaries(&self, limit: i64, before: Option<i64>) -> Result<Vec<SlotSummary>> {
+        let client = self.pool.get().await?;
+
+        let rows = client.query(
+            &format!(r#"
+            SELECT {}
+            FROM slot_summaries s
+            LEFT JOIN slot_conflict_graphs g ON g.slot = s.slot
+            WHERE $1::int8 IS NULL OR s.slot < $1
+            ORDER BY s.slot DESC
+            LIMIT $2
+            "#, SLOT_SUMMARY_COLUMNS),
+            &[&before, &limit],
+        ).await?;
+
+        Ok(rows.iter().map(SlotSummary::from_row).collect())
+    }
+
+    /// Get the summary of a single slot
+    pub async fn get_slot_summary(&self, slot: i64) -> Result<Option<SlotSummary>> {
+        let client = self.pool.get().await?;
+
+        let row = client.query_opt(
+            &format!(r#"
+            SELECT {}
+            FROM slot_summaries s
+            LEFT JOIN slot_conflict_graphs g ON g.slot = s.slot
+            WHERE s.slot = $1
+            "#, SLOT_SUMMARY_COLUMNS),
+            &[&slot],
+        ).await?;
+
+        Ok(row.as_ref().map(SlotSummary::from_row))
+    }
+
+    /// Get per-leader aggregates over the time window, leaders with most slots first.
+    /// Hot accounts are those with 5+ writers in the slot, as on the dashboard.
+    pub async fn get_leader_stats(&self, limit: i64, minutes: i64) -> Result<Vec<LeaderStats>> {
+        let client = self.pool.get().await?;
+
+        let rows = client.query(
+            r#"
+            WITH slot_events AS (
+                SELECT
+                    slot,
+                    SUM(priority_fee_lamports) as sum_priority_fee,
+                    COUNT(priority_fee_lamports) as fee_count,
+                    COUNT(*) FILTER (WHERE lock_contention_score >= 5) as hot_lock_attempts,
+                    COUNT(*) FILTER (WHERE lock_contention_score >= 5 AND success) as hot_successful_locks
+                FROM write_lock_events
+                WHERE time >= NOW() - ($1::text || ' minutes')::INTERVAL
+                GROUP BY slot
+            )
+            SELECT
+                s.leader,
+                COUNT(*) as slots,
+                COUNT(*) FILTER (WHERE s.skipped) as skipped_slots,
+                AVG(s.block_fullness_pct) FILTER (WHERE NOT s.skipped)::float8 as avg_block_fullness_pct,
+                (SUM(e.sum_priority_fee) / NULLIF(SUM(e.fee_count), 0))::float8 as avg_priority_fee,
+                COALESCE(SUM(e.hot_lock_attempts), 0)::int8 as hot_lock_attempts,
+                COALESCE(SUM(e.hot_successful_locks), 0)::int8 as hot_successful_locks
+            FROM slot_summaries s
+            LEFT JOIN slot_events e ON e.slot = s.slot
+            WHERE s.time >= NOW() - ($1::text || ' minutes')::INTERVAL
+              AND s.leader IS NOT NULL
+            GROUP BY s.leader
+            ORDER BY slots DESC, s.leader
+            LIMIT $2
+            "#,
+            &[&minutes.to_string(), &limit],
+        ).await?;
+
+        Ok(rows.iter().map(|row| LeaderStats {
+            leader: row.get("leader"),
+            slots: row.get("slots"),
+            skipped_slots: row.get("skipped_slots"),
+            avg_block_fullness_pct: row.get("avg_block_fullness_pct"),
+            avg_priority_fee: row.get("avg_priority_fee"),
+            hot_lock_attempts: row.get("hot_lock_attempts"),
+            hot_successful_locks: row.get("hot_successful_locks"),
+        }).collect())
+    }
+
+    /// Get the conflict graph of a block and the accounts that serialize the most of it
+    pub async fn get_slot_conflicts(&self, slot: i64) -> Result<Option<SlotConflicts>> {
+        let client = self.pool.get().await?;
+
+        let row = client.query_opt(
+            r#"
+            SELECT
+                slot,
+                transaction_count,
+                component_count,
+                largest_component,
+                longest_chain,
+                parallelism_factor,
+                bottleneck_account,
+                bottleneck_writers
+            FROM slot_conflict_graphs
+            WHERE slot = $1
+            "#,
+            &[&slot],
+        ).await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let accounts = client.query(
+            r#"
+            SELECT
+                account_pubkey,
+                COUNT(*) as writers,
+                SUM(compute_units_consumed)::int8 as compute_units
+            FROM write_lock_events
+            WHERE slot = $1
+            GROUP BY account_pubkey
+            HAVING COUNT(*) > 1
+            ORDER BY writers DESC, compute_units DESC NULLS LAST
+            LIMIT 10
+            "#,
+            &[&slot],
+        ).await?;
+
+        Ok(Some(SlotConflicts {
+            slot: row.get("slot"),
+            transaction_count: row.get("transaction_count"),
+            component_count: row.get("component_count"),
+            largest_component: row.get("largest_component"),
+            longest_chain: row.get("longest_chain"),
+            parallelism_factor: row.get("parallelism_factor"),
+            bottleneck_account: row.get("bottleneck_account"),
+            bottleneck_writers: row.get("bottleneck_writers"),
+            top_accounts: accounts.iter().map(|row| SerializingAccount {
+                account_pubkey: row.get("account_pubkey"),
+                writers: row.get("writers"),
+                compute_units: row.get("compute_units"),
+            }).collect(),
+        }))
+    }
+
+    /// Count failed transactions writing an account, or attributed to a program,
+    /// by failure reason and error code
+    pub async fn get_failure_breakdown(
+        &self,
+        scope: FailureScope,
+        key: &str,
+        hours: i64,
+    ) -> Result<FailureBreakdown> {
+        let client = self.pool.get().await?;
+
+        // A transaction may write several accounts of the same program
+        let totals = client.query_one(
+            &format!(
+                r#"
+                SELECT
+                    COUNT(DISTINCT transaction_signature) as transactions,
+                    COUNT(DISTINCT transaction_signature) FILTER (WHERE NOT success) as failed_transactions
+                FROM write_lock_events
+                WHERE {column} = $1
+                  AND time >= NOW() - ($2::text || ' hours')::INTERVAL
+                "#,
+                column = scope.column()
+            ),
+            &[&key, &hours.to_string()],
+        ).await?;
+
+        let reasons = client.query(
+            &format!(
+                r#"
+                SELECT
+                    COALESCE(failure_reason, 'unclassified') as reason,
+                    error_code,
+                    COUNT(DISTINCT transaction_signature) as failures
+                FROM write_lock_events
+                WHERE {column} = $1
+                  AND time >= NOW() - ($2::text || ' hours')::INTERVAL
+                  AND NOT success
+                GROUP BY 1, 2
+                ORDER BY failures DESC, reason
+                LIMIT 20
+                "#,
+                column = scope.column()
+            ),
+            &[&key, &hours.to_string()],
+        ).await?;
+
+        Ok(FailureBreakdown {
+            transactions: totals.get("transactions"),
+            failed_transactions: totals.get("failed_transactions"),
+            reasons: reasons.iter().map(|row| FailureReasonCount {
+                reason: row.get("reason"),
+                error_code: row.get("error_code"),
+                failures: row.get("failures"),
+            }).collect(),
+        })
+    }
+
+    /// Fee payers write-locking an account, busiest first, and how concentrated
+    /// the attempts are among them
+    pub async fn get_account_signers(
+        &self,
+        pubkey: &str,
+        hours: i64,
+        limit: i64,
+    ) -> Result<AccountSigners> {
+        let client = self.pool.get().await?;
+
+        // Totals cover every signer, only the top ones are returned. The LEFT JOIN keeps
+        // the totals row when there are no events or the limit is zero.
+        let rows = client.query(
+            r#"
+            WITH per_signer AS (
+                SELECT
+                    fee_payer,
+                    COUNT(*) as lock_attempts,
+                    COUNT(*) FILTER (WHERE success) as successful_locks,
+                    AVG(priority_fee_lamports)::float8 as avg_priority_fee,
+                    AVG(tip_lamports)::float8 as avg_tip
+                FROM write_lock_events
+                WHERE account_pubkey = $1
+                  AND time >= NOW() - ($2::text || ' hours')::INTERVAL
+                  AND fee_payer IS NOT NULL
+                GROUP BY fee_payer
+            ),
+            totals AS (
+                SELECT
+                    COALESCE(SUM(lock_attempts), 0)::int8 as total_attempts,
+                    COUNT(*) as unique_signers,
+                    COALESCE(SUM(lock_attempts::float8 ^ 2) / SUM(lock_attempts)::float8 ^ 2, 0) as hhi
+                FROM per_signer
+            ),
+            top_signers AS (
+                SELECT * FROM per_signer
+                ORDER BY lock_attempts DESC, fee_payer
+                LIMIT $3
+            )
+            SELECT t.total_attempts, t.unique_signers, t.hhi, s.*
+            FROM totals t
+            LEFT JOIN top_signers s ON true
+            ORDER BY s.lock_attempts DESC, s.fee_payer
+            "#,
+            &[&pubkey, &hours.to_string(), &limit.max(0)],
+        ).await?;
+
+        let totals = &rows[0];
+        let top_signers = rows.iter()
+            .filter_map(|row| Some(SignerActivity {
+                fee_payer: row.get::<_, Option<String>>("fee_payer")?,
+                lock_attempts: row.get("lock_attempts"),
+                successful_locks: row.get("successful_locks"),
+                avg_priority_fee: row.get("avg_priority_fee"),
+                avg_tip: row.get("avg_tip"),
+            }))
+            .collect();
+
+        Ok(AccountSigners {
+            lock_attempts: totals.get("total_attempts"),
+            unique_signers: totals.get("unique_signers"),
+            hhi: totals.get("hhi"),
+            top_signers,
+        })
+    }
+
+    /// Fee-payer concentration of the accounts with the most write lock attempts
+    pub async fn get_signer_concentration(
+        &self,
+        limit: i64,
+        minutes: i64,
+    ) -> Result<Vec<SignerConcentration>> {
+        let client = self.pool.get().await?;
+
+        let rows = client.query(
+            r#"
+            WITH per_signer AS (
+                SELECT account_pubkey, fee_payer, COUNT(*)::float8 as attempts
+                FROM write_lock_events
+                WHERE time >= NOW() - ($1::text || ' minutes')::INTERVAL
+                  AND fee_payer IS NOT NULL
+                GROUP BY account_pubkey, fee_payer
+            ),
+            per_account AS (
+                SELECT account_pubkey, SUM(attempts) as attempts
+                FROM per_signer
+                GROUP BY account_pubkey
+                ORDER BY attempts DESC
+                LIMIT $2
+            )
+            SELECT
+                a.account_pubkey,
+                a.attempts::int8 as lock_attempts,
+                COUNT(*) as unique_signers,
+                MAX(s.attempts) / a.attempts as top_signer_share,
+                SUM((s.attempts / a.attempts) ^ 2) as hhi
+            FROM per_account a
+            JOIN per_signer s ON s.account_pubkey = a.account_pubkey
+            GROUP BY a.account_pubkey, a.attempts
+            ORDER BY lock_attempts DESC, a.account_pubkey
+            "#,
+            &[&minutes.to_string(), &limit],
+        ).await?;
+
+        Ok(rows.iter().map(|row| SignerConcentration {
+            account_pubkey: row.get("account_pubkey"),
+            lock_attempts: row.get("lock_attempts"),
+            unique_signers: row.get("unique_signers"),
+            top_signer_share: row.get("top_signer_share"),
+            hhi: row.get("hhi"),
+        }).collect())
+    }
+
+    /// Success rate and fees of one fee payer's transactions
+    pub async fn get_signer_stats(&self, fee_payer: &str, hours: i64) -> Result<SignerStats> {
+        let client = self.pool.get().await?;
+
+        // Events are per written account, fees and outcome are per transaction
+        let row = client.query_one(
+            r#"
+            WITH txs AS (
+                SELECT
+                    transaction_signature,
+                    BOOL_AND(success) as success,
+                    MAX(priority_fee_lamports) as priority_fee,
+                    MAX(tip_lamports) as tip
+                FROM write_lock_events
+                WHERE fee_payer = $1
+                  AND time >= NOW() - ($2::text || ' hours')::INTERVAL
+                GROUP BY transaction_signature
+            )
+            SELECT
+                COUNT(*) as transactions,
+                COUNT(*) FILTER (WHERE success) as successful_transactions,
+                (
+                    SELECT COUNT(DISTINCT account_pubkey)
+                    FROM write_lock_events
+                    WHERE fee_payer = $1
+                      AND time >= NOW() - ($2::text || ' hours')::INTERVAL
+                ) as accounts_written,
+                AVG(priority_fee)::float8 as avg_priority_fee,
+                AVG(tip)::float8 as avg_tip
+            FROM txs
+            "#,
+            &[&fee_payer, &hours.to_string()],
+        ).await?;
+
+        Ok(SignerStats {
+            transactions: row.get("transactions"),
+            successful_transactions: row.get("successful_transactions"),
+            accounts_written: row.get("accounts_written"),
+            avg_priority_fee: row.get("avg_priority_fee"),
+            avg_tip: row.get("avg_tip"),
+        })
+    }
+
+    /// Aggregate write contention and fees over the token accounts of a mint
+    pub async fn get_mint_stats(&self, mint: &str, hours: i64) -> Result<Option<MintStats>> {
+        let client = self.pool.get().await?;
+
+        let row = client.query_one(
+            r#"
+            SELECT
+                COUNT(DISTINCT account_pubkey) as token_accounts,
+                COUNT(*) as lock_attempts,
+                COUNT(*) FILTER (WHERE success) as successful_locks,
+                AVG(lock_contention_score)::float8 as avg_contention,
+                MAX(lock_contention_score)::float8 as max_contention,
+                AVG(priority_fee_lamports)::float8 as avg_priority_fee,
+                MAX(priority_fee_lamports) as max_priority_fee,
+                AVG(tip_lamports)::float8 as avg_tip
+            FROM write_lock_events
+            WHERE mint = $1
+              AND time >= NOW() - ($2::text || ' hours')::INTERVAL
+            "#,
+            &[&mint, &hours.to_string()],
+        ).await?;
+
+        let lock_attempts: i64 = row.get("lock_attempts");
+        if lock_attempts == 0 {
+            return Ok(None);
+        }
+
+        let accounts = client.query(
+            r#"
+            SELECT
+                account_pubkey,
+                COUNT(*) as lock_attempts,
+                AVG(lock_contention_score)::float8 as avg_contention
+            FROM write_lock_events
+            WHERE mint = $1
+              AND time >= NOW() - ($2::text || ' hours')::INTERVAL
+            GROUP BY account_pubkey
+            ORDER BY avg_contention DESC NULLS LAST, lock_attempts DESC
+            LIMIT 10
+            "#,
+            &[&mint, &hours.to_string()],
+        ).await?;
+
+        Ok(Some(MintStats {
+            token_accounts: row.get("token_accounts"),
+            lock_attempts,
+            successful_locks: row.get("successful_locks"),
+            avg_contention: row.get("avg_contention"),
+            max_contention: row.get("max_contention"),
+            avg_priority_fee: row.get("avg_priority_fee"),
+            max_priority_fee: row.get("max_priority_fee"),
+            avg_tip: row.get("avg_tip"),
+            top_accounts: accounts.iter().map(|row| MintAccount {
+                account_pubkey: row.get("account_pubkey"),
+                lock_attempts: row.get("lock_attempts"),
+                avg_contention: row.get("avg_contention"),
+            }).collect(),
+        }))
+    }
+
     /// Get current average priority fee for accounts with high contention
     pub async fn get_recommended_priority_fee(
         &self,
         accounts: &[String],
-    ) -> Result<i64> {
+    ) -> Result<PriorityFeeEstimate> {
         if accounts.is_empty() {
-            return Ok(0);
+            return Ok(PriorityFeeEstimate::default());
         }
 
         let client = self.pool.get().await?;
@@ -184,7 +885,11 @@ impl Database {
         let rows = client.query(
             r#"
             SELECT
-                PERCENTILE_CONT(0.75) WITHIN GROUP (ORDER BY priority_fee_lamports) as recommended_fee
+                PERCENTILE_CONT(0.75) WITHIN GROUP (ORDER BY priority_fee_lamports) as recommended_fee,
+                PERCENTILE_CONT(0.75) WITHIN GROUP (ORDER BY compute_unit_price_micro_lamports) as recommended_cu_price,
+                PERCENTILE_CONT(0.75) WITHIN GROUP (ORDER BY tip_lamports)
+                    FILTER (WHERE tip_lamports > 0) as recommended_tip,
+                (COUNT(*) FILTER (WHERE tip_lamports > 0))::float8 / NULLIF(COUNT(*), 0) * 100 as tipped_pct
             FROM write_lock_events
             WHERE account_pubkey = ANY($1)
               AND time >= NOW() - INTERVAL '1 hour'
@@ -195,11 +900,19 @@ impl Database {
         ).await?;
 
         if rows.is_empty() {
-            return Ok(0);
+            return Ok(PriorityFeeEstimate::default());
         }
 
         let fee: Option<f64> = rows[0].get("recommended_fee");
-        Ok(fee.unwrap_or(0.0) as i64)
+        let cu_price: Option<f64> = rows[0].get("recommended_cu_price");
+        let tip: Option<f64> = rows[0].get("recommended_tip");
+        let tipped_pct: Option<f64> = rows[0].get("tipped_pct");
+        Ok(PriorityFeeEstimate {
+            priority_fee: fee.unwrap_or(0.0) as i64,
+            cu_price_micro_lamports: cu_price.unwrap_or(0.0) as i64,
+            tip_lamports: tip.unwrap_or(0.0) as i64,
+            tipped_pct: tipped_pct.unwrap_or(0.0),
+        })
     }
 
     /// Get LIVE fee estimate for an account (P90 of last 10 slots + 20% buffer)
@@ -217,7 +930,10 @@ impl Database {
                 COUNT(*) as tx_count,
                 AVG(lock_contention_score)::float8 as avg_contention,
                 MAX(priority_fee_lamports) as max_fee,
-                PERCENTILE_CONT(0.9) WITHIN GROUP (ORDER BY priority_fee_lamports) as p90_fee
+                PERCENTILE_CONT(0.9) WITHIN GROUP (ORDER BY priority_fee_lamports) as p90_fee,
+                PERCENTILE_CONT(0.9) WITHIN GROUP (ORDER BY compute_unit_price_micro_lamports) as p90_cu_price,
+                PERCENTILE_CONT(0.9) WITHIN GROUP (ORDER BY tip_lamports)
+                    FILTER (WHERE tip_lamports > 0) as p90_tip
             FROM write_lock_events
             WHERE account_pubkey = $1
               AND time >= NOW() - INTERVAL '30 seconds'
@@ -235,6 +951,10 @@ impl Database {
                 queue_depth: 0,
                 p90_fee: 0,
                 recommended_fee: 0,
+                p90_cu_price: 0,
+                recommended_cu_price: 0,
+                p90_tip: 0,
+                recommended_tip: 0,
                 avg_contention: 0.0,
                 slots_observed: 0,
             });
@@ -261,11 +981,35 @@ impl Database {
         // Recommended = P90 + 20% buffer
         let recommended_fee = (p90_fee as f64 * 1.2) as i64;
 
+        // Same for the CU price, which is what clients actually set
+        let mut all_cu_prices: Vec<i64> = rows.iter()
+            .filter_map(|r| r.get::<_, Option<f64>>("p90_cu_price").map(|p| p as i64))
+            .collect();
+        all_cu_prices.sort();
+
+        let p90_cu_idx = (all_cu_prices.len() as f64 * 0.9).ceil() as usize;
+        let p90_cu_price = all_cu_prices.get(p90_cu_idx.saturating_sub(1)).copied().unwrap_or(0);
+        let recommended_cu_price = (p90_cu_price as f64 * 1.2) as i64;
+
+        // Jito tips are bid on their own, and only by the transactions that tip
+        let mut all_tips: Vec<i64> = rows.iter()
+            .filter_map(|r| r.get::<_, Option<f64>>("p90_tip").map(|t| t as i64))
+            .collect();
+        all_tips.sort();
+
+        let p90_tip_idx = (all_tips.len() as f64 * 0.9).ceil() as usize;
+        let p90_tip = all_tips.get(p90_tip_idx.saturating_sub(1)).copied().unwrap_or(0);
+        let recommended_tip = (p90_tip as f64 * 1.2) as i64;
+
         Ok(LiveFeeEstimate {
             account: pubkey.to_string(),
             queue_depth: queue_depth as u32,
             p90_fee,
             recommended_fee,
+            p90_cu_price,
+            recommended_cu_price,
+            p90_tip,
+            recommended_tip,
             avg_contention,
             slots_observed: rows.len(),
         })
@@ -279,7 +1023,24 @@ pub struct LiveFeeEstimate {
     pub queue_depth: u32,
     pub p90_fee: i64,
     pub recommended_fee: i64,
+    pub p90_cu_price: i64,
+    pub recommended_cu_price: i64,
+    /// Jito tips of the tipping transactions, separate from the priority fee
+    pub p90_tip: i64,
+    pub recommended_tip: i64,
     pub avg_contention: f64,
     pub slots_observed: usize,
 }
 
+/// Historical priority fee estimate (P75 of successful transactions)
+#[derive(Debug, Clone, Default)]
+pub struct PriorityFeeEstimate {
+    /// Priority component only, base signature fee excluded
+    pub priority_fee: i64,
+    pub cu_price_micro_lamports: i64,
+    /// Jito tip of the transactions that tip, on top of the priority fee
+    pub tip_lamports: i64,
+    /// Share of transactions that tip, in percent
+    pub tipped_pct: f64,
+}
+
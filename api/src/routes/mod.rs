@@ -5,9 +5,9 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::database::Database;
+use crate::database::{Database, FailureBreakdown, FailureScope, HotAccountSort, SlotSummary};
 
-// GET /api/hot-accounts?limit=20&window=5
+// GET /api/hot-accounts?limit=20&window=5&sort=contention|transactions|saturation
 #[derive(Debug, Deserialize)]
 pub struct HotAccountsQuery {
     #[serde(default = "default_limit")]
@@ -15,6 +15,9 @@ pub struct HotAccountsQuery {
     
     #[serde(default = "default_window")]
     pub window: i64,  // minutes
+
+    #[serde(default)]
+    pub sort: HotAccountSort,
 }
 
 fn default_limit() -> i64 {
@@ -33,6 +36,12 @@ pub struct HotAccountResponse {
     pub success_rate: f64,
     pub avg_contention: f64,
     pub max_contention: f64,
+    /// Reads of the account in slots where it was also written (each one is
+    /// serialized against the writers)
+    pub read_conflicts: i64,
+    /// Average per-slot share of the per-account block CU limit, in percent
+    pub avg_cu_saturation: f64,
+    pub max_cu_saturation: f64,
     pub avg_priority_fee: i64,
     pub max_priority_fee: i64,
 }
@@ -42,7 +51,7 @@ pub async fn hot_accounts(
     State(db): State<Database>,
 ) -> Result<Json<Vec<HotAccountResponse>>, StatusCode> {
     let accounts = db
-        .get_hot_accounts(params.limit, params.window)
+        .get_hot_accounts(params.limit, params.window, params.sort)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
@@ -59,6 +68,9 @@ pub async fn hot_accounts(
             },
             avg_contention: acc.avg_contention.unwrap_or(0.0),
             max_contention: acc.max_contention.unwrap_or(0.0),
+            read_conflicts: acc.read_conflicts,
+            avg_cu_saturation: acc.avg_cu_saturation.unwrap_or(0.0),
+            max_cu_saturation: acc.max_cu_saturation.unwrap_or(0.0),
             avg_priority_fee: acc.avg_priority_fee.unwrap_or(0.0) as i64,
             max_priority_fee: acc.max_priority_fee.unwrap_or(0),
         })
@@ -74,6 +86,9 @@ pub struct DashboardStatsResponse {
     pub total_events: i64,
     pub high_contention_accounts: i64,
     pub avg_success_rate: f64,
+    pub avg_ingest_lag_seconds: f64,
+    pub vote_transactions: i64,
+    pub non_vote_transactions: i64,
 }
 
 pub async fn dashboard_stats(
@@ -90,6 +105,9 @@ pub async fn dashboard_stats(
         total_events: stats.total_events,
         high_contention_accounts: stats.high_contention_accounts,
         avg_success_rate: stats.avg_success_rate,
+        avg_ingest_lag_seconds: stats.avg_ingest_lag_seconds,
+        vote_transactions: stats.vote_transactions,
+        non_vote_transactions: stats.non_vote_transactions,
     }))
 }
 
@@ -112,6 +130,11 @@ pub struct AccountStatsResponse {
     pub failed_locks: i64,
     pub success_rate: f64,
     pub avg_contention: f64,
+    pub read_conflicts: i64,
+    pub avg_cu_saturation: f64,
+    pub max_cu_saturation: f64,
+    /// Per-account block CU limit the saturation is measured against
+    pub account_cu_limit: i64,
     pub avg_priority_fee: i64,
     pub max_priority_fee: i64,
 }
@@ -138,8 +161,481 @@ pub async fn account_stats(
                 0.0
             },
             avg_contention: s.avg_contention.unwrap_or(0.0),
+            read_conflicts: s.read_conflicts,
+            avg_cu_saturation: s.avg_cu_saturation.unwrap_or(0.0),
+            max_cu_saturation: s.max_cu_saturation.unwrap_or(0.0),
+            account_cu_limit: db.account_cu_limit(),
+            avg_priority_fee: s.avg_priority_fee.unwrap_or(0.0) as i64,
+            max_priority_fee: s.max_priority_fee.unwrap_or(0),
+        })),
+        None => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+// GET /api/accounts/:pubkey/failures?window=24
+// GET /api/programs/:program_id/failures?window=24
+#[derive(Debug, Serialize)]
+pub struct FailureBreakdownResponse {
+    /// Account, or program the writes are attributed to
+    pub pubkey: String,
+    pub window_hours: i64,
+    pub transactions: i64,
+    pub failed_transactions: i64,
+    pub failure_rate: f64,
+    pub reasons: Vec<FailureReasonResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FailureReasonResponse {
+    pub reason: String,
+    pub error_code: Option<i64>,
+    pub failures: i64,
+    /// Share of the failed transactions, in percent
+    pub share: f64,
+}
+
+impl FailureBreakdownResponse {
+    fn new(pubkey: String, window_hours: i64, breakdown: FailureBreakdown) -> Self {
+        Self {
+            pubkey,
+            window_hours,
+            transactions: breakdown.transactions,
+            failed_transactions: breakdown.failed_transactions,
+            failure_rate: percent(breakdown.failed_transactions, breakdown.transactions),
+            reasons: breakdown.reasons
+                .into_iter()
+                .map(|r| FailureReasonResponse {
+                    share: percent(r.failures, breakdown.failed_transactions),
+                    reason: r.reason,
+                    error_code: r.error_code,
+                    failures: r.failures,
+                })
+                .collect(),
+        }
+    }
+}
+
+pub async fn account_failures(
+    Path(pubkey): Path<String>,
+    Query(params): Query<AccountStatsQuery>,
+    State(db): State<Database>,
+) -> Result<Json<FailureBreakdownResponse>, StatusCode> {
+    let breakdown = db
+        .get_failure_breakdown(FailureScope::Account, &pubkey, params.window)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(FailureBreakdownResponse::new(pubkey, params.window, breakdown)))
+}
+
+pub async fn program_failures(
+    Path(program_id): Path<String>,
+    Query(params): Query<AccountStatsQuery>,
+    State(db): State<Database>,
+) -> Result<Json<FailureBreakdownResponse>, StatusCode> {
+    let breakdown = db
+        .get_failure_breakdown(FailureScope::Program, &program_id, params.window)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(FailureBreakdownResponse::new(program_id, params.window, breakdown)))
+}
+
+// GET /api/accounts/:pubkey/signers?window=24&limit=20
+#[derive(Debug, Deserialize)]
+pub struct AccountSignersQuery {
+    #[serde(default = "default_stats_window")]
+    pub window: i64,  // hours
+
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountSignersResponse {
+    pub pubkey: String,
+    pub window_hours: i64,
+    pub lock_attempts: i64,
+    pub unique_signers: i64,
+    /// Herfindahl index of the signers' shares, 0 to 1 (a single signer)
+    pub hhi: f64,
+    pub signers: Vec<SignerResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SignerResponse {
+    pub fee_payer: String,
+    pub lock_attempts: i64,
+    /// Share of the account's lock attempts, in percent
+    pub share: f64,
+    pub success_rate: f64,
+    pub avg_priority_fee: i64,
+    pub avg_tip: i64,
+}
+
+fn percent(count: i64, total: i64) -> f64 {
+    if total > 0 {
+        count as f64 / total as f64 * 100.0
+    } else {
+        0.0
+    }
+}
+
+pub async fn account_signers(
+    Path(pubkey): Path<String>,
+    Query(params): Query<AccountSignersQuery>,
+    State(db): State<Database>,
+) -> Result<Json<AccountSignersResponse>, StatusCode> {
+    let signers = db
+        .get_account_signers(&pubkey, params.window, params.limit)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(AccountSignersResponse {
+        pubkey,
+        window_hours: params.window,
+        lock_attempts: signers.lock_attempts,
+        unique_signers: signers.unique_signers,
+        hhi: signers.hhi,
+        signers: signers.top_signers
+            .into_iter()
+            .map(|s| SignerResponse {
+                share: percent(s.lock_attempts, signers.lock_attempts),
+                success_rate: percent(s.successful_locks, s.lock_attempts),
+                fee_payer: s.fee_payer,
+                lock_attempts: s.lock_attempts,
+                avg_priority_fee: s.avg_priority_fee.unwrap_or(0.0) as i64,
+                avg_tip: s.avg_tip.unwrap_or(0.0) as i64,
+            })
+            .collect(),
+    }))
+}
+
+// GET /api/hot-accounts/concentration?limit=20&window=5
+#[derive(Debug, Serialize)]
+pub struct SignerConcentrationResponse {
+    pub account_pubkey: String,
+    pub lock_attempts: i64,
+    pub unique_signers: i64,
+    /// Share of the lock attempts sent by the busiest signer, in percent
+    pub top_signer_share: f64,
+    pub hhi: f64,
+}
+
+pub async fn signer_concentration(
+    Query(params): Query<HotAccountsQuery>,
+    State(db): State<Database>,
+) -> Result<Json<Vec<SignerConcentrationResponse>>, StatusCode> {
+    let accounts = db
+        .get_signer_concentration(params.limit, params.window)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(accounts
+        .into_iter()
+        .map(|a| SignerConcentrationResponse {
+            account_pubkey: a.account_pubkey,
+            lock_attempts: a.lock_attempts,
+            unique_signers: a.unique_signers,
+            top_signer_share: a.top_signer_share * 100.0,
+            hhi: a.hhi,
+        })
+        .collect()))
+}
+
+// GET /api/signers/:pubkey/stats?window=24
+#[derive(Debug, Serialize)]
+pub struct SignerStatsResponse {
+    pub fee_payer: String,
+    pub window_hours: i64,
+    pub transactions: i64,
+    pub successful_transactions: i64,
+    pub success_rate: f64,
+    pub accounts_written: i64,
+    pub avg_priority_fee: i64,
+    pub avg_tip: i64,
+}
+
+pub async fn signer_stats(
+    Path(fee_payer): Path<String>,
+    Query(params): Query<AccountStatsQuery>,
+    State(db): State<Database>,
+) -> Result<Json<SignerStatsResponse>, StatusCode> {
+    let stats = db
+        .get_signer_stats(&fee_payer, params.window)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if stats.transactions == 0 {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    Ok(Json(SignerStatsResponse {
+        fee_payer,
+        window_hours: params.window,
+        transactions: stats.transactions,
+        successful_transactions: stats.successful_transactions,
+        success_rate: percent(stats.successful_transactions, stats.transactions),
+        accounts_written: stats.accounts_written,
+        avg_priority_fee: stats.avg_priority_fee.unwrap_or(0.0) as i64,
+        avg_tip: stats.avg_tip.unwrap_or(0.0) as i64,
+    }))
+}
+
+// GET /api/mints/:mint/stats?window=24
+#[derive(Debug, Serialize)]
+pub struct MintStatsResponse {
+    pub mint: String,
+    pub window_hours: i64,
+    /// Token accounts of the mint written in the window (vaults, user accounts...)
+    pub token_accounts: i64,
+    pub lock_attempts: i64,
+    pub success_rate: f64,
+    pub avg_contention: f64,
+    pub max_contention: f64,
+    pub avg_priority_fee: i64,
+    pub max_priority_fee: i64,
+    pub avg_tip: i64,
+    pub top_accounts: Vec<MintAccountResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MintAccountResponse {
+    pub account_pubkey: String,
+    pub lock_attempts: i64,
+    pub avg_contention: f64,
+}
+
+pub async fn mint_stats(
+    Path(mint): Path<String>,
+    Query(params): Query<AccountStatsQuery>,
+    State(db): State<Database>,
+) -> Result<Json<MintStatsResponse>, StatusCode> {
+    let stats = db
+        .get_mint_stats(&mint, params.window)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    match stats {
+        Some(s) => Ok(Json(MintStatsResponse {
+            mint,
+            window_hours: params.window,
+            token_accounts: s.token_accounts,
+            lock_attempts: s.lock_attempts,
+            success_rate: percent(s.successful_locks, s.lock_attempts),
+            avg_contention: s.avg_contention.unwrap_or(0.0),
+            max_contention: s.max_contention.unwrap_or(0.0),
             avg_priority_fee: s.avg_priority_fee.unwrap_or(0.0) as i64,
             max_priority_fee: s.max_priority_fee.unwrap_or(0),
+            avg_tip: s.avg_tip.unwrap_or(0.0) as i64,
+            top_accounts: s.top_accounts
+                .into_iter()
+                .map(|a| MintAccountResponse {
+                    account_pubkey: a.account_pubkey,
+                    lock_attempts: a.lock_attempts,
+                    avg_contention: a.avg_contention.unwrap_or(0.0),
+                })
+                .collect(),
+        })),
+        None => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+// GET /api/slots?limit=50&before=<slot>
+#[derive(Debug, Deserialize)]
+pub struct SlotsQuery {
+    #[serde(default = "default_slots_limit")]
+    pub limit: i64,
+    /// Only slots lower than this one, to page back in time
+    pub before: Option<i64>,
+}
+
+fn default_slots_limit() -> i64 {
+    50
+}
+
+#[derive(Debug, Serialize)]
+pub struct SlotSummaryResponse {
+    pub slot: i64,
+    /// Unix seconds
+    pub block_time: i64,
+    pub time_estimated: bool,
+    pub skipped: bool,
+    pub parent_slot: Option<i64>,
+    pub leader: Option<String>,
+    pub transaction_count: i32,
+    pub vote_transaction_count: i32,
+    pub non_vote_transaction_count: i32,
+    pub failed_transaction_count: i32,
+    pub total_compute_units: i64,
+    /// total_compute_units as a percentage of the block CU limit
+    pub block_fullness_pct: f32,
+    pub total_fees_lamports: i64,
+    pub total_priority_fees_lamports: i64,
+    pub longest_chain: Option<i32>,
+    pub parallelism_factor: Option<f32>,
+    /// Writers of the most contended account in the block
+    pub max_contention: Option<i32>,
+}
+
+impl From<SlotSummary> for SlotSummaryResponse {
+    fn from(s: SlotSummary) -> Self {
+        Self {
+            slot: s.slot,
+            block_time: s.block_time.timestamp(),
+            time_estimated: s.time_estimated,
+            skipped: s.skipped,
+            parent_slot: s.parent_slot,
+            leader: s.leader,
+            transaction_count: s.transaction_count,
+            vote_transaction_count: s.vote_transaction_count,
+            non_vote_transaction_count: s.transaction_count - s.vote_transaction_count,
+            failed_transaction_count: s.failed_transaction_count,
+            total_compute_units: s.total_compute_units,
+            block_fullness_pct: s.block_fullness_pct,
+            total_fees_lamports: s.total_fees_lamports,
+            total_priority_fees_lamports: s.total_priority_fees_lamports,
+            longest_chain: s.longest_chain,
+            parallelism_factor: s.parallelism_factor,
+            max_contention: s.bottleneck_writers,
+        }
+    }
+}
+
+pub async fn slots(
+    Query(params): Query<SlotsQuery>,
+    State(db): State<Database>,
+) -> Result<Json<Vec<SlotSummaryResponse>>, StatusCode> {
+    let summaries = db
+        .get_slot_summaries(params.limit.clamp(1, 1000), params.before)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(summaries.into_iter().map(SlotSummaryResponse::from).collect()))
+}
+
+// GET /api/slots/:slot
+pub async fn slot(
+    Path(slot): Path<i64>,
+    State(db): State<Database>,
+) -> Result<Json<SlotSummaryResponse>, StatusCode> {
+    let summary = db
+        .get_slot_summary(slot)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    summary
+        .map(|s| Json(SlotSummaryResponse::from(s)))
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+// GET /api/leaders?limit=20&window=60
+#[derive(Debug, Deserialize)]
+pub struct LeadersQuery {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+
+    #[serde(default = "default_leaders_window")]
+    pub window: i64,  // minutes
+}
+
+fn default_leaders_window() -> i64 {
+    60
+}
+
+#[derive(Debug, Serialize)]
+pub struct LeaderStatsResponse {
+    pub leader: String,
+    pub slots: i64,
+    pub skipped_slots: i64,
+    pub avg_block_fullness_pct: f64,
+    pub avg_priority_fee: i64,
+    /// Write locks on accounts with 5+ writers in the slot
+    pub hot_lock_attempts: i64,
+    pub hot_success_rate: f64,
+}
+
+pub async fn leaders(
+    Query(params): Query<LeadersQuery>,
+    State(db): State<Database>,
+) -> Result<Json<Vec<LeaderStatsResponse>>, StatusCode> {
+    let leaders = db
+        .get_leader_stats(params.limit, params.window)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let response = leaders
+        .into_iter()
+        .map(|l| LeaderStatsResponse {
+            leader: l.leader,
+            slots: l.slots,
+            skipped_slots: l.skipped_slots,
+            avg_block_fullness_pct: l.avg_block_fullness_pct.unwrap_or(0.0),
+            avg_priority_fee: l.avg_priority_fee.unwrap_or(0.0) as i64,
+            hot_lock_attempts: l.hot_lock_attempts,
+            hot_success_rate: if l.hot_lock_attempts > 0 {
+                (l.hot_successful_locks as f64 / l.hot_lock_attempts as f64) * 100.0
+            } else {
+                0.0
+            },
+        })
+        .collect();
+
+    Ok(Json(response))
+}
+
+// GET /api/slots/:slot/conflicts
+#[derive(Debug, Serialize)]
+pub struct SlotConflictsResponse {
+    pub slot: i64,
+    pub transaction_count: i32,
+    /// Groups of transactions linked by shared write locks
+    pub component_count: i32,
+    pub largest_component: i32,
+    /// Transactions that must run one after another on the longest path
+    pub longest_chain: i32,
+    /// transaction_count / longest_chain
+    pub parallelism_factor: f32,
+    pub bottleneck_account: Option<String>,
+    pub bottleneck_writers: i32,
+    /// Accounts with more than one writer, most writers first
+    pub top_accounts: Vec<SerializingAccountResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SerializingAccountResponse {
+    pub account_pubkey: String,
+    pub writers: i64,
+    pub compute_units: i64,
+}
+
+pub async fn slot_conflicts(
+    Path(slot): Path<i64>,
+    State(db): State<Database>,
+) -> Result<Json<SlotConflictsResponse>, StatusCode> {
+    let conflicts = db
+        .get_slot_conflicts(slot)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    match conflicts {
+        Some(c) => Ok(Json(SlotConflictsResponse {
+            slot: c.slot,
+            transaction_count: c.transaction_count,
+            component_count: c.component_count,
+            largest_component: c.largest_component,
+            longest_chain: c.longest_chain,
+            parallelism_factor: c.parallelism_factor,
+            bottleneck_account: c.bottleneck_account,
+            bottleneck_writers: c.bottleneck_writers,
+            top_accounts: c.top_accounts
+                .into_iter()
+                .map(|a| SerializingAccountResponse {
+                    account_pubkey: a.account_pubkey,
+                    writers: a.writers,
+                    compute_units: a.compute_units.unwrap_or(0),
+                })
+                .collect(),
         })),
         None => Err(StatusCode::NOT_FOUND),
     }
@@ -155,6 +651,10 @@ pub struct PriorityFeeRequest {
 pub struct PriorityFeeResponse {
     pub recommended_fee_lamports: i64,
     pub recommended_fee_sol: f64,
+    pub recommended_cu_price_micro_lamports: i64,
+    /// Jito tip, paid on top of the priority fee by the transactions that tip
+    pub recommended_tip_lamports: i64,
+    pub tipped_pct: f64,
 }
 
 pub async fn estimate_priority_fee(
@@ -165,14 +665,17 @@ pub async fn estimate_priority_fee(
         return Err(StatusCode::BAD_REQUEST);
     }
 
-    let fee = db
+    let estimate = db
         .get_recommended_priority_fee(&payload.accounts)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     Ok(Json(PriorityFeeResponse {
-        recommended_fee_lamports: fee,
-        recommended_fee_sol: fee as f64 / 1_000_000_000.0,
+        recommended_fee_lamports: estimate.priority_fee,
+        recommended_fee_sol: estimate.priority_fee as f64 / 1_000_000_000.0,
+        recommended_cu_price_micro_lamports: estimate.cu_price_micro_lamports,
+        recommended_tip_lamports: estimate.tip_lamports,
+        tipped_pct: estimate.tipped_pct,
     }))
 }
 
@@ -185,6 +688,10 @@ pub struct LiveFeeResponse {
     pub p90_fee_lamports: i64,
     pub recommended_fee_lamports: i64,
     pub recommended_fee_sol: f64,
+    pub p90_cu_price_micro_lamports: i64,
+    pub recommended_cu_price_micro_lamports: i64,
+    pub p90_tip_lamports: i64,
+    pub recommended_tip_lamports: i64,
     pub avg_contention: f64,
     pub slots_observed: usize,
     pub freshness_seconds: i64,
@@ -205,6 +712,10 @@ pub async fn fee_now(
         p90_fee_lamports: estimate.p90_fee,
         recommended_fee_lamports: estimate.recommended_fee,
         recommended_fee_sol: estimate.recommended_fee as f64 / 1_000_000_000.0,
+        p90_cu_price_micro_lamports: estimate.p90_cu_price,
+        recommended_cu_price_micro_lamports: estimate.recommended_cu_price,
+        p90_tip_lamports: estimate.p90_tip,
+        recommended_tip_lamports: estimate.recommended_tip,
         avg_contention: estimate.avg_contention,
         slots_observed: estimate.slots_observed,
         freshness_seconds: 30, // Data from last 30 seconds
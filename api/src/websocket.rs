@@ -9,7 +9,7 @@ use futures::{sink::SinkExt, stream::StreamExt};
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
-use crate::database::Database;
+use crate::database::{Database, HotAccountSort};
 
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type")]
@@ -71,7 +71,7 @@ async fn handle_socket(socket: WebSocket, db: Database) {
             interval.tick().await;
             
             // Fetch hot accounts
-            match db_clone.get_hot_accounts(20, 5).await {
+            match db_clone.get_hot_accounts(20, 5, HotAccountSort::Contention).await {
                 Ok(accounts) => {
                     let data: Vec<HotAccountData> = accounts
                         .iter()
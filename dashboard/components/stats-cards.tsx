@@ -30,8 +30,8 @@ export function StatsCards() {
 
     if (isLoading || !stats) {
         return (
-            <div className="grid grid-cols-2 lg:grid-cols-4 gap-4">
-                {[...Array(4)].map((_, i) => (
+            <div className="grid grid-cols-2 lg:grid-cols-3 gap-4">
+                {[...Array(6)].map((_, i) => (
                     <div key={i} className="card p-8">
                         <div className="skeleton h-3 w-24 mb-6" />
                         <div className="skeleton h-12 w-20" />
@@ -42,11 +42,13 @@ export function StatsCards() {
     }
 
     return (
-        <div className="grid grid-cols-2 lg:grid-cols-4 gap-4">
+        <div className="grid grid-cols-2 lg:grid-cols-3 gap-4">
             <Stat label="Accounts Tracked" value={stats.unique_accounts} />
             <Stat label="High Contention" value={stats.high_contention_accounts} />
             <Stat label="Total Events" value={stats.total_events} />
             <Stat label="Success Rate" value={stats.avg_success_rate.toFixed(1)} suffix="%" />
+            <Stat label="Non-vote Txs" value={stats.non_vote_transactions} />
+            <Stat label="Vote Txs" value={stats.vote_transactions} />
         </div>
     );
 }
@@ -5,6 +5,9 @@ export interface HotAccount {
     success_rate: number;
     avg_contention: number;
     max_contention: number;
+    read_conflicts: number;
+    avg_cu_saturation: number;
+    max_cu_saturation: number;
     avg_priority_fee: number;
     max_priority_fee: number;
 }
@@ -16,6 +19,10 @@ export interface AccountStats {
     failed_locks: number;
     success_rate: number;
     avg_contention: number;
+    read_conflicts: number;
+    avg_cu_saturation: number;
+    max_cu_saturation: number;
+    account_cu_limit: number;
     avg_priority_fee: number;
     max_priority_fee: number;
 }
@@ -25,6 +32,9 @@ export interface DashboardStats {
     total_events: number;
     high_contention_accounts: number;
     avg_success_rate: number;
+    avg_ingest_lag_seconds: number;
+    vote_transactions: number;
+    non_vote_transactions: number;
 }
 
 const API_BASE_URL = process.env.NEXT_PUBLIC_API_URL || "http://localhost:3001";
@@ -89,6 +99,8 @@ export interface LiveFeeEstimate {
     p90_fee_lamports: number;
     recommended_fee_lamports: number;
     recommended_fee_sol: number;
+    p90_tip_lamports: number;
+    recommended_tip_lamports: number;
     avg_contention: number;
     slots_observed: number;
     freshness_seconds: number;
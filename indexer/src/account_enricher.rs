@@ -0,0 +1,223 @@
+use anyhow::Result;
+use chrono::Utc;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::database::{AccountMetadata, EventSink};
+use crate::known_programs::{get_account_label, get_program_label, AMM_PROGRAMS, ORACLE_PROGRAMS, ORDERBOOK_PROGRAMS};
+use crate::live_tracker::LiveTracker;
+
+/// How often the live tracker is checked for newly hot accounts
+pub const ENRICH_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Age in hours at which stored metadata is looked up again
+pub const DEFAULT_REFRESH_HOURS: u64 = 24;
+
+/// Accounts per `getMultipleAccounts` call (the RPC maximum)
+const ACCOUNTS_PER_REQUEST: usize = 100;
+
+/// Hot accounts considered per pass, by live contention
+const HOT_ACCOUNT_LIMIT: usize = 200;
+
+/// Average writers per slot for an account to count as hot
+const MIN_CONTENTION: f32 = 2.0;
+
+const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
+const TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const TOKEN_2022_PROGRAM: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+// Sizes of SPL token accounts and mints without extensions. Token-2022 accounts with
+// extensions are longer and store their type in the byte after the token account layout.
+const TOKEN_ACCOUNT_LEN: usize = 165;
+const MINT_LEN: usize = 82;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const ACCOUNT_TYPE_TOKEN_ACCOUNT: u8 = 2;
+
+/// What an account is, judged from its owner, size and executable flag
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountType {
+    /// Owned by an AMM program: pool state, vaults, tick arrays
+    AmmPool,
+    OrderbookMarket,
+    Oracle,
+    TokenAccount,
+    Mint,
+    /// System-owned account without data
+    Wallet,
+    Program,
+    Other,
+}
+
+pub fn classify(account: &Account) -> AccountType {
+    if account.executable {
+        return AccountType::Program;
+    }
+
+    let owner = account.owner.to_string();
+    match owner.as_str() {
+        SYSTEM_PROGRAM if account.data.is_empty() => AccountType::Wallet,
+        TOKEN_PROGRAM | TOKEN_2022_PROGRAM => {
+            match (account.data.len(), account.data.get(TOKEN_ACCOUNT_LEN)) {
+                (MINT_LEN, _) | (_, Some(&ACCOUNT_TYPE_MINT)) => AccountType::Mint,
+                (TOKEN_ACCOUNT_LEN, _) | (_, Some(&ACCOUNT_TYPE_TOKEN_ACCOUNT)) => AccountType::TokenAccount,
+                _ => AccountType::Other,
+            }
+        }
+        owner if AMM_PROGRAMS.contains(&owner) => AccountType::AmmPool,
+        owner if ORDERBOOK_PROGRAMS.contains(&owner) => AccountType::OrderbookMarket,
+        owner if ORACLE_PROGRAMS.contains(&owner) => AccountType::Oracle,
+        _ => AccountType::Other,
+    }
+}
+
+/// Looks up accounts once they turn hot and stores what they are in `account_metadata`
+pub struct AccountEnricher {
+    client: Arc<RpcClient>,
+    refresh_after: Duration,
+    /// When accounts that didn't exist were looked up. They have no metadata to
+    /// tell, and are dropped once `refresh_after` has passed.
+    missing: HashMap<String, Instant>,
+}
+
+impl AccountEnricher {
+    pub fn new(client: Arc<RpcClient>) -> Self {
+        Self {
+            client,
+            refresh_after: Duration::from_secs(DEFAULT_REFRESH_HOURS * 60 * 60),
+            missing: HashMap::new(),
+        }
+    }
+
+    pub fn with_refresh_after(mut self, refresh_after: Duration) -> Self {
+        self.refresh_after = refresh_after;
+        self
+    }
+
+    /// Enrich the live tracker's hot accounts that weren't looked up recently
+    pub async fn enrich_hot_accounts(&mut self, live_tracker: &LiveTracker, sink: &dyn EventSink) -> Result<usize> {
+        let hot: Vec<String> = live_tracker.get_hot_accounts(HOT_ACCOUNT_LIMIT).await
+            .into_iter()
+            .filter(|estimate| estimate.avg_contention >= MIN_CONTENTION)
+            .map(|estimate| estimate.account)
+            .collect();
+
+        self.enrich(&hot, sink).await
+    }
+
+    /// Fetch the accounts not looked up within `refresh_after` in batches and store
+    /// their metadata. Returns the number of accounts stored.
+    pub async fn enrich(&mut self, pubkeys: &[String], sink: &dyn EventSink) -> Result<usize> {
+        let refresh_after = self.refresh_after;
+        self.missing.retain(|_, looked_up| looked_up.elapsed() < refresh_after);
+        let unknown: Vec<String> = pubkeys.iter()
+            .filter(|pubkey| !self.missing.contains_key(*pubkey))
+            .cloned()
+            .collect();
+        let fresh = sink.enriched_since(&unknown, Utc::now() - chrono::Duration::from_std(refresh_after)?).await?;
+        let new: Vec<Pubkey> = unknown.iter()
+            .filter(|pubkey| !fresh.contains(*pubkey))
+            .filter_map(|pubkey| Pubkey::from_str(pubkey).ok())
+            .collect();
+
+        let mut stored = 0;
+        for chunk in new.chunks(ACCOUNTS_PER_REQUEST) {
+            let accounts = self.client.get_multiple_accounts(chunk).await?;
+            let mut metadata = Vec::new();
+            for (pubkey, account) in chunk.iter().zip(accounts) {
+                match account {
+                    Some(account) => metadata.push(account_metadata(pubkey, &account)),
+                    None => {
+                        self.missing.insert(pubkey.to_string(), Instant::now());
+                    }
+                }
+            }
+
+            sink.upsert_account_metadata(&metadata).await?;
+            stored += metadata.len();
+        }
+
+        Ok(stored)
+    }
+}
+
+fn account_metadata(pubkey: &Pubkey, account: &Account) -> AccountMetadata {
+    let pubkey = pubkey.to_string();
+    AccountMetadata {
+        label: get_account_label(&pubkey).or_else(|| get_program_label(&pubkey)),
+        pubkey,
+        program_id: account.owner.to_string(),
+        data_len: account.data.len() as i64,
+        executable: account.executable,
+        account_type: classify(account),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::MemorySink;
+    use lock_test_support::{MockChain, MockRpcServer};
+    use solana_sdk::commitment_config::CommitmentConfig;
+
+    #[tokio::test]
+    async fn test_accounts_are_classified_and_refetched_after_a_while() {
+        let raydium_pool = "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2";
+        let (vault, wallet, oracle, closed) = (
+            Pubkey::new_unique().to_string(),
+            Pubkey::new_unique().to_string(),
+            Pubkey::new_unique().to_string(),
+            Pubkey::new_unique().to_string(),
+        );
+        let chain = MockChain::new()
+            .with_account(raydium_pool, AMM_PROGRAMS[0], 752, false)
+            .with_account(&vault, TOKEN_PROGRAM, 165, false)
+            .with_account(&wallet, SYSTEM_PROGRAM, 0, false)
+            .with_account(&oracle, ORACLE_PROGRAMS[1], 134, false)
+            .with_account(AMM_PROGRAMS[0], "BPFLoaderUpgradeab1e11111111111111111111111", 36, true);
+        let server = MockRpcServer::start(chain).await;
+        let client = Arc::new(RpcClient::new_with_commitment(server.url(), CommitmentConfig::confirmed()));
+        let mut enricher = AccountEnricher::new(client.clone());
+        let sink = MemorySink::default();
+
+        let pubkeys = [raydium_pool, &vault, &wallet, &oracle, &closed, AMM_PROGRAMS[0]].map(str::to_string);
+        assert_eq!(enricher.enrich(&pubkeys, &sink).await.unwrap(), 5);
+
+        let stored: Vec<_> = sink.metadata.lock().unwrap().iter()
+            .map(|m| (m.pubkey.clone(), m.account_type, m.label, m.data_len))
+            .collect();
+        assert_eq!(stored, vec![
+            (raydium_pool.to_string(), AccountType::AmmPool, Some("Raydium SOL-USDC"), 752),
+            (vault, AccountType::TokenAccount, None, 165),
+            (wallet, AccountType::Wallet, None, 0),
+            (oracle, AccountType::Oracle, None, 134),
+            (AMM_PROGRAMS[0].to_string(), AccountType::Program, Some("Raydium AMM"), 36),
+        ]);
+
+        // Everything, the missing account included, was looked up in one call
+        assert_eq!(enricher.enrich(&pubkeys, &sink).await.unwrap(), 0);
+        assert_eq!(server.calls("getMultipleAccounts"), 1);
+
+        // Stored metadata goes stale, also for an enricher started later
+        let mut enricher = AccountEnricher::new(client).with_refresh_after(Duration::ZERO);
+        assert_eq!(enricher.enrich(&pubkeys, &sink).await.unwrap(), 5);
+        assert_eq!(server.calls("getMultipleAccounts"), 2);
+    }
+
+    #[test]
+    fn test_token_2022_accounts_with_extensions() {
+        let mut account = Account {
+            owner: TOKEN_2022_PROGRAM.parse().unwrap(),
+            data: vec![0; 250],
+            ..Account::default()
+        };
+        account.data[TOKEN_ACCOUNT_LEN] = ACCOUNT_TYPE_MINT;
+        assert_eq!(classify(&account), AccountType::Mint);
+
+        account.data[TOKEN_ACCOUNT_LEN] = ACCOUNT_TYPE_TOKEN_ACCOUNT;
+        assert_eq!(classify(&account), AccountType::TokenAccount);
+    }
+}
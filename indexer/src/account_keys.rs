@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use solana_sdk::{message::VersionedMessage, pubkey::Pubkey};
+use solana_transaction_status::{option_serializer::OptionSerializer, UiTransactionStatusMeta};
+
+/// Where a transaction's account key came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountSource {
+    /// Listed directly in the message's static account keys
+    Static,
+    /// Loaded through an address lookup table (v0 transactions)
+    LookupTable,
+}
+
+/// A single account referenced by a transaction
+#[derive(Debug, Clone)]
+pub struct TransactionAccount {
+    pub pubkey: Pubkey,
+    pub writable: bool,
+    pub source: AccountSource,
+}
+
+/// Resolve the full account list of a transaction.
+///
+/// Accounts are returned in runtime order: static keys first, then lookup table
+/// writable addresses, then lookup table readonly addresses. This is the order
+/// instruction account indexes refer to.
+pub fn resolve_accounts(
+    message: &VersionedMessage,
+    meta: Option<&UiTransactionStatusMeta>,
+) -> Vec<TransactionAccount> {
+    let mut accounts: Vec<TransactionAccount> = message
+        .static_account_keys()
+        .iter()
+        .enumerate()
+        .map(|(i, key)| TransactionAccount {
+            pubkey: *key,
+            writable: message.is_maybe_writable(i),
+            source: AccountSource::Static,
+        })
+        .collect();
+
+    if let Some(OptionSerializer::Some(loaded)) = meta.map(|m| &m.loaded_addresses) {
+        let writable = loaded.writable.iter().map(|key| (key, true));
+        let readonly = loaded.readonly.iter().map(|key| (key, false));
+
+        for (key, writable) in writable.chain(readonly) {
+            if let Ok(pubkey) = Pubkey::from_str(key) {
+                accounts.push(TransactionAccount {
+                    pubkey,
+                    writable,
+                    source: AccountSource::LookupTable,
+                });
+            }
+        }
+    }
+
+    accounts
+}
+
+/// Mint of each token account the transaction touches. Token balances refer to
+/// accounts by their index in `accounts`, which must be in runtime order.
+pub fn token_mints(
+    meta: Option<&UiTransactionStatusMeta>,
+    accounts: &[TransactionAccount],
+) -> HashMap<Pubkey, String> {
+    let mut mints = HashMap::new();
+    let Some(meta) = meta else {
+        return mints;
+    };
+
+    // Accounts closed by the transaction only have a pre balance, new ones only a post balance
+    for balances in [&meta.pre_token_balances, &meta.post_token_balances] {
+        let OptionSerializer::Some(balances) = balances else { continue };
+        for balance in balances {
+            if let Some(account) = accounts.get(balance.account_index as usize) {
+                mints.entry(account.pubkey).or_insert_with(|| balance.mint.clone());
+            }
+        }
+    }
+
+    mints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{hash::Hash, message::{v0, MessageHeader}};
+    use solana_transaction_status::UiLoadedAddresses;
+
+    fn meta_with_loaded(writable: &[Pubkey], readonly: &[Pubkey]) -> UiTransactionStatusMeta {
+        serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "loadedAddresses": UiLoadedAddresses {
+                writable: writable.iter().map(|k| k.to_string()).collect(),
+                readonly: readonly.iter().map(|k| k.to_string()).collect(),
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_lookup_table_accounts_are_merged() {
+        let payer = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let pool = Pubkey::new_unique();
+        let oracle = Pubkey::new_unique();
+
+        let message = VersionedMessage::V0(v0::Message {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            },
+            account_keys: vec![payer, program],
+            recent_blockhash: Hash::default(),
+            instructions: vec![],
+            address_table_lookups: vec![v0::MessageAddressTableLookup {
+                account_key: Pubkey::new_unique(),
+                writable_indexes: vec![0],
+                readonly_indexes: vec![1],
+            }],
+        });
+        let meta = meta_with_loaded(&[pool], &[oracle]);
+
+        let accounts = resolve_accounts(&message, Some(&meta));
+        let summary: Vec<_> = accounts.iter()
+            .map(|a| (a.pubkey, a.writable, a.source))
+            .collect();
+
+        assert_eq!(summary, vec![
+            (payer, true, AccountSource::Static),
+            (program, false, AccountSource::Static),
+            (pool, true, AccountSource::LookupTable),
+            (oracle, false, AccountSource::LookupTable),
+        ]);
+    }
+
+    #[test]
+    fn test_token_mints_follow_runtime_order() {
+        let (payer, vault, new_account) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let (usdc, sol) = (Pubkey::new_unique().to_string(), Pubkey::new_unique().to_string());
+        let accounts: Vec<_> = [payer, vault, new_account].iter()
+            .map(|&pubkey| TransactionAccount { pubkey, writable: true, source: AccountSource::Static })
+            .collect();
+
+        let balance = |index: u8, mint: &str| serde_json::json!({
+            "accountIndex": index,
+            "mint": mint,
+            "uiTokenAmount": { "amount": "1", "decimals": 6, "uiAmount": 0.000001, "uiAmountString": "0.000001" },
+        });
+        let meta: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "preTokenBalances": [balance(1, &usdc)],
+            "postTokenBalances": [balance(1, &usdc), balance(2, &sol), balance(9, &sol)],
+        }))
+        .unwrap();
+
+        let mints = token_mints(Some(&meta), &accounts);
+        assert_eq!(mints.len(), 2);
+        assert_eq!(mints[&vault], usdc);
+        assert_eq!(mints[&new_account], sol);
+    }
+}
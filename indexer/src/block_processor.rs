@@ -0,0 +1,532 @@
+use chrono::{DateTime, Utc};
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{EncodedTransactionWithStatusMeta, UiConfirmedBlock};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+
+use crate::account_keys::{resolve_accounts, token_mints};
+use crate::compute_budget::fee_breakdown;
+use crate::conflict_graph;
+use crate::database::{ReadLockEvent, SlotBatch, SlotConflictGraph, SlotSummary, WriteLockEvent};
+use crate::failure_reason::classify;
+use crate::jito_tips::detect_tip;
+use crate::lock_detector::LockDetector;
+use crate::write_attribution::attribute_accounts;
+
+/// Target slot duration, used to estimate block times the RPC doesn't report
+pub const SLOT_DURATION_MS: i64 = 400;
+
+/// Compute units a whole block may consume, until feature activations raise it
+pub const DEFAULT_MAX_BLOCK_COMPUTE_UNITS: u64 = 48_000_000;
+
+/// Most transactions Jito lands in one bundle. A bundle lands back to back and
+/// usually tips in its last transaction, so a tipping transaction is taken to close
+/// a bundle when one of the transactions just before it (within this many, and
+/// after any earlier tip) shares a signer with it. That transaction, the tip and
+/// everything between them are flagged; between them can be a searcher's target.
+/// A tipping transaction on its own isn't flagged.
+const MAX_BUNDLE_TRANSACTIONS: i32 = 5;
+
+const VOTE_PROGRAM_ID: &str = "Vote111111111111111111111111111111111111111";
+
+/// Write activity of one account within a slot, as fed to the live tracker
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AccountActivity {
+    pub contention: f32,
+}
+
+/// Everything derived from a single block
+pub struct ProcessedBlock {
+    pub batch: SlotBatch,
+    pub accounts: HashMap<String, AccountActivity>,
+}
+
+/// Turns blocks into write lock events. Holds no I/O, so it behaves the same for
+/// every block source.
+pub struct BlockProcessor {
+    /// Most recent (slot, block_time) reported by the RPC, anchor for estimates
+    last_block_time: Option<(u64, i64)>,
+    /// Track locks of vote transactions too. They are always counted in slot summaries.
+    include_vote_transactions: bool,
+    /// Block fullness is measured against this
+    max_block_compute_units: u64,
+}
+
+impl Default for BlockProcessor {
+    fn default() -> Self {
+        Self {
+            last_block_time: None,
+            include_vote_transactions: false,
+            max_block_compute_units: DEFAULT_MAX_BLOCK_COMPUTE_UNITS,
+        }
+    }
+}
+
+impl BlockProcessor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_vote_transactions(mut self, include: bool) -> Self {
+        self.include_vote_transactions = include;
+        self
+    }
+
+    pub fn with_max_block_compute_units(mut self, limit: u64) -> Self {
+        self.max_block_compute_units = limit.max(1);
+        self
+    }
+
+    /// Resolve the timestamp for a block, estimating it from slot timing when
+    /// the RPC has no `block_time`. Returns the time and whether it was estimated.
+    fn resolve_block_time(&mut self, slot: u64, block_time: Option<i64>) -> (DateTime<Utc>, bool) {
+        if let Some(secs) = block_time {
+            if let Some(time) = DateTime::from_timestamp(secs, 0) {
+                self.last_block_time = Some((slot, secs));
+                return (time, false);
+            }
+        }
+
+        let estimated = self.last_block_time.and_then(|(anchor_slot, anchor_secs)| {
+            let offset_ms = (slot as i64 - anchor_slot as i64) * SLOT_DURATION_MS;
+            DateTime::from_timestamp_millis(anchor_secs * 1000 + offset_ms)
+        });
+
+        (estimated.unwrap_or_else(Utc::now), true)
+    }
+
+    /// Detect locks in a block: one event per writable account of each transaction, and
+    /// one read event per readonly account that some other transaction writes
+    pub fn process(&mut self, slot: u64, block: &UiConfirmedBlock) -> ProcessedBlock {
+        let (block_time, time_estimated) = self.resolve_block_time(slot, block.block_time);
+        let ingested_at = Utc::now();
+
+        let mut detector = LockDetector::new();
+        let mut events = Vec::new();
+        let mut reads = Vec::new();
+        let mut accounts: HashMap<String, AccountActivity> = HashMap::new();
+        let mut account_compute_units: HashMap<String, i64> = HashMap::new();
+        // Positions in the block of the transactions that tip
+        let mut tipping: HashSet<i32> = HashSet::new();
+        // Signers of each tracked transaction, by position in the block
+        let mut signers: HashMap<i32, HashSet<Pubkey>> = HashMap::new();
+        let mut summary = SlotSummary {
+            time: block_time,
+            time_estimated,
+            ingested_at,
+            slot: slot as i64,
+            skipped: false,
+            parent_slot: Some(block.parent_slot as i64),
+            // The processor does no I/O, the caller fills this in
+            leader: None,
+            transaction_count: 0,
+            vote_transaction_count: 0,
+            failed_transaction_count: 0,
+            total_compute_units: 0,
+            total_fees_lamports: 0,
+            total_priority_fees_lamports: 0,
+            block_fullness_pct: 0.0,
+        };
+
+        if let Some(transactions) = &block.transactions {
+            for (tx_index, tx_with_meta) in transactions.iter().enumerate() {
+                if let Some(transaction) = &tx_with_meta.transaction.decode() {
+                    let signature = transaction.signatures[0].to_string();
+                    let message = &transaction.message;
+                    let fee_payer = message.static_account_keys()[0].to_string();
+
+                    let fees = fee_breakdown(message);
+                    let priority_fee = fees.priority_fee_lamports as i64;
+                    let compute_units = extract_compute_units(tx_with_meta);
+                    // Unknown without a status rather than counted as a failure
+                    let success = tx_with_meta.meta.as_ref().map(|m| m.status.is_ok());
+                    let failure = tx_with_meta.meta.as_ref().and_then(classify);
+
+                    // Extract program IDs from instructions
+                    let program_ids: Vec<String> = message.instructions().iter()
+                        .map(|ix| message.static_account_keys()[ix.program_id_index as usize].to_string())
+                        .collect();
+
+                    let is_vote = program_ids.iter().any(|p| p == VOTE_PROGRAM_ID);
+
+                    summary.transaction_count += 1;
+                    if is_vote {
+                        summary.vote_transaction_count += 1;
+                    }
+                    if success == Some(false) {
+                        summary.failed_transaction_count += 1;
+                    }
+                    summary.total_compute_units += compute_units.unwrap_or(0) as i64;
+                    summary.total_fees_lamports += tx_with_meta.meta.as_ref().map_or(0, |m| m.fee as i64);
+                    summary.total_priority_fees_lamports += priority_fee;
+
+                    // Votes run on their own banking thread and would drown out the
+                    // contention on program accounts
+                    if is_vote && !self.include_vote_transactions {
+                        continue;
+                    }
+
+                    // Static keys plus any addresses loaded from lookup tables
+                    let resolved = resolve_accounts(message, tx_with_meta.meta.as_ref());
+                    let attributed = attribute_accounts(message, tx_with_meta.meta.as_ref(), &resolved);
+                    let tip = detect_tip(message, tx_with_meta.meta.as_ref(), &resolved);
+                    let mints = token_mints(tx_with_meta.meta.as_ref(), &resolved);
+                    if tip.account.is_some() {
+                        tipping.insert(tx_index as i32);
+                    }
+                    let signer_count = message.header().num_required_signatures as usize;
+                    signers.insert(tx_index as i32, message.static_account_keys().iter().take(signer_count).copied().collect());
+                    let (writable_accounts, readonly_accounts): (Vec<_>, Vec<_>) =
+                        resolved.into_iter().partition(|account| account.writable);
+
+                    // Invoked programs are read-locked too, but are never writable
+                    let invoked: HashSet<_> = message.instructions().iter()
+                        .map(|ix| message.static_account_keys()[ix.program_id_index as usize])
+                        .collect();
+                    let readonly_accounts: Vec<_> = readonly_accounts.into_iter()
+                        .filter(|account| !invoked.contains(&account.pubkey))
+                        .collect();
+
+                    if !writable_accounts.is_empty() {
+                        let keys: Vec<_> = writable_accounts.iter().map(|a| a.pubkey).collect();
+                        detector.track_transaction(&signature, &keys);
+                    }
+                    if !readonly_accounts.is_empty() {
+                        let keys: Vec<_> = readonly_accounts.iter().map(|a| a.pubkey).collect();
+                        detector.track_reads(&signature, &keys);
+                    }
+
+                    for account in &writable_accounts {
+                        let account_str = account.pubkey.to_string();
+                        // Writers tracked so far, this transaction included
+                        let queue_position = detector.calculate_contention(&account_str) as i32;
+
+                        // Contention is filled in once the whole block has been seen
+                        accounts.entry(account_str.clone()).or_insert(AccountActivity { contention: 0.0 });
+                        *account_compute_units.entry(account_str.clone()).or_default() +=
+                            compute_units.unwrap_or(0) as i64;
+                        let attribution = attributed.get(&account.pubkey);
+
+                        let event = WriteLockEvent {
+                            time: block_time,
+                            time_estimated,
+                            ingested_at,
+                            slot: slot as i64,
+                            transaction_index: tx_index as i32,
+                            account_pubkey: account_str,
+                            account_source: account.source,
+                            program_id: attribution.map(|a| a.program_id.to_string()),
+                            invoking_program_id: attribution.map(|a| a.invoking_program_id.to_string()),
+                            instruction_index: attribution.map(|a| a.instruction_index as i32),
+                            transaction_signature: signature.clone(),
+                            fee_payer: fee_payer.clone(),
+                            mint: mints.get(&account.pubkey).cloned(),
+                            success,
+                            failure_reason: failure.as_ref().map(|f| f.reason),
+                            error_instruction_index: failure.as_ref().and_then(|f| f.instruction_index).map(i32::from),
+                            error_code: failure.as_ref().and_then(|f| f.error_code).map(i64::from),
+                            // Filled in once the whole block has been seen
+                            lock_contention_score: 0.0,
+                            queue_position,
+                            read_conflicts: 0,
+                            compute_unit_price_micro_lamports: Some(fees.compute_unit_price_micro_lamports as i64),
+                            compute_unit_limit: Some(fees.compute_unit_limit as i32),
+                            base_fee_lamports: Some(fees.base_fee_lamports as i64),
+                            priority_fee_lamports: Some(priority_fee),
+                            compute_units_consumed: compute_units,
+                            account_compute_units: 0,
+                            tip_lamports: tip.lamports as i64,
+                            likely_bundle: false,
+                        };
+
+                        events.push(event);
+                    }
+
+                    // Kept only if the account turns out to be written in this block
+                    for account in &readonly_accounts {
+                        reads.push(ReadLockEvent {
+                            time: block_time,
+                            time_estimated,
+                            ingested_at,
+                            slot: slot as i64,
+                            transaction_index: tx_index as i32,
+                            account_pubkey: account.pubkey.to_string(),
+                            account_source: account.source,
+                            program_id: attributed.get(&account.pubkey).map(|a| a.program_id.to_string()),
+                            transaction_signature: signature.clone(),
+                            success,
+                            writer_count: 0,
+                        });
+                    }
+                }
+            }
+        }
+
+        // See `MAX_BUNDLE_TRANSACTIONS`
+        let mut bundled: HashSet<i32> = HashSet::new();
+        for &tip_index in &tipping {
+            let tipper = &signers[&tip_index];
+            let first = (tip_index + 1 - MAX_BUNDLE_TRANSACTIONS..tip_index).rev()
+                .take_while(|index| *index >= 0 && !tipping.contains(index))
+                .filter(|index| signers.get(index).is_some_and(|other| !other.is_disjoint(tipper)))
+                .last();
+            if let Some(first) = first {
+                bundled.extend(first..=tip_index);
+            }
+        }
+
+        // Contention and CU usage are totals over the whole block, not the counts at
+        // the time each transaction was seen
+        for event in events.iter_mut() {
+            event.lock_contention_score = detector.calculate_contention(&event.account_pubkey);
+            event.read_conflicts = detector.read_conflicts(&event.account_pubkey) as i32;
+            event.account_compute_units = account_compute_units[&event.account_pubkey];
+            event.likely_bundle = bundled.contains(&event.transaction_index);
+        }
+        reads.retain(|read| detector.has_writers(&read.account_pubkey));
+        for read in reads.iter_mut() {
+            read.writer_count = detector.calculate_contention(&read.account_pubkey) as i32;
+        }
+        for (account, activity) in accounts.iter_mut() {
+            activity.contention = detector.calculate_contention(account);
+        }
+
+        summary.block_fullness_pct =
+            (summary.total_compute_units as f64 / self.max_block_compute_units as f64 * 100.0) as f32;
+
+        let graph = conflict_graph::analyze(&detector);
+        let conflict_graph = SlotConflictGraph {
+            time: block_time,
+            slot: slot as i64,
+            transaction_count: graph.transaction_count as i32,
+            component_count: graph.component_count as i32,
+            largest_component: graph.largest_component as i32,
+            longest_chain: graph.longest_chain as i32,
+            parallelism_factor: graph.parallelism_factor,
+            bottleneck_account: graph.bottleneck_account,
+            bottleneck_writers: graph.bottleneck_writers as i32,
+        };
+
+        ProcessedBlock {
+            batch: SlotBatch {
+                events,
+                reads,
+                conflict_graphs: vec![conflict_graph],
+                summaries: vec![summary],
+            },
+            accounts,
+        }
+    }
+
+    /// Record a slot that produced no block
+    pub fn process_skipped(&mut self, slot: u64) -> ProcessedBlock {
+        let (time, time_estimated) = self.resolve_block_time(slot, None);
+        let summary = SlotSummary {
+            time,
+            time_estimated,
+            ingested_at: Utc::now(),
+            slot: slot as i64,
+            skipped: true,
+            parent_slot: None,
+            leader: None,
+            transaction_count: 0,
+            vote_transaction_count: 0,
+            failed_transaction_count: 0,
+            total_compute_units: 0,
+            total_fees_lamports: 0,
+            total_priority_fees_lamports: 0,
+            block_fullness_pct: 0.0,
+        };
+
+        ProcessedBlock {
+            batch: SlotBatch { summaries: vec![summary], ..Default::default() },
+            accounts: HashMap::new(),
+        }
+    }
+}
+
+/// Extract compute units from transaction metadata
+fn extract_compute_units(tx: &EncodedTransactionWithStatusMeta) -> Option<i32> {
+    tx.meta.as_ref().and_then(|m| match m.compute_units_consumed {
+        OptionSerializer::Some(cu) => Some(cu as i32),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jito_tips::JITO_TIP_ACCOUNTS;
+    use std::collections::{BTreeMap, BTreeSet};
+    use solana_sdk::hash::Hash;
+    use solana_sdk::instruction::CompiledInstruction;
+    use solana_sdk::message::{Message, VersionedMessage};
+    use solana_sdk::signature::Signature;
+    use solana_sdk::system_instruction;
+    use solana_sdk::transaction::VersionedTransaction;
+    use solana_transaction_status::{TransactionStatusMeta, UiTransactionEncoding, VersionedTransactionWithStatusMeta};
+
+    /// A legacy transaction with one instruction of `program` over `writable` and `readonly`
+    fn transaction(program: Pubkey, writable: &[Pubkey], readonly: &[Pubkey]) -> EncodedTransactionWithStatusMeta {
+        transaction_from(Pubkey::new_unique(), program, writable, readonly)
+    }
+
+    fn transaction_from(payer: Pubkey, program: Pubkey, writable: &[Pubkey], readonly: &[Pubkey]) -> EncodedTransactionWithStatusMeta {
+        let mut keys = vec![payer];
+        keys.extend_from_slice(writable);
+        keys.extend_from_slice(readonly);
+        keys.push(program);
+
+        let instruction = CompiledInstruction::new_from_raw_parts(
+            (keys.len() - 1) as u8,
+            Vec::new(),
+            (1..keys.len() as u8 - 1).collect(),
+        );
+        let message = Message::new_with_compiled_instructions(
+            1, 0, readonly.len() as u8 + 1, keys, Hash::default(), vec![instruction],
+        );
+
+        VersionedTransactionWithStatusMeta {
+            transaction: VersionedTransaction {
+                signatures: vec![Signature::new_unique()],
+                message: VersionedMessage::Legacy(message),
+            },
+            meta: TransactionStatusMeta::default(),
+        }
+        .encode(UiTransactionEncoding::Base64, Some(0), false)
+        .unwrap()
+    }
+
+    /// A transaction tipping `tip_account` from its fee payer
+    fn tipping_transaction(payer: Pubkey, tip_account: &str) -> EncodedTransactionWithStatusMeta {
+        let tip = system_instruction::transfer(&payer, &tip_account.parse().unwrap(), 10_000);
+
+        VersionedTransactionWithStatusMeta {
+            transaction: VersionedTransaction {
+                signatures: vec![Signature::new_unique()],
+                message: VersionedMessage::Legacy(Message::new(&[tip], Some(&payer))),
+            },
+            meta: TransactionStatusMeta::default(),
+        }
+        .encode(UiTransactionEncoding::Base64, Some(0), false)
+        .unwrap()
+    }
+
+    fn block(transactions: Vec<EncodedTransactionWithStatusMeta>) -> UiConfirmedBlock {
+        UiConfirmedBlock {
+            previous_blockhash: Hash::default().to_string(),
+            blockhash: Hash::default().to_string(),
+            parent_slot: 99,
+            transactions: Some(transactions),
+            signatures: None,
+            rewards: None,
+            block_time: Some(1_717_000_000),
+            block_height: None,
+        }
+    }
+
+    #[test]
+    fn test_reads_of_written_accounts_are_conflicts() {
+        let (program, pool, oracle) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let processed = BlockProcessor::new().process(100, &block(vec![
+            transaction(program, &[pool], &[oracle]),
+            transaction(program, &[], &[pool, oracle]),
+            transaction(program, &[], &[pool]),
+        ]));
+
+        let write = processed.batch.events.iter().find(|e| e.account_pubkey == pool.to_string()).unwrap();
+        assert_eq!(write.read_conflicts, 2);
+
+        // Nobody writes the oracle, and the invoked program is never stored as a read
+        let reads: Vec<_> = processed.batch.reads.iter()
+            .map(|r| (r.transaction_index, r.account_pubkey.clone(), r.writer_count))
+            .collect();
+        assert_eq!(reads, vec![(1, pool.to_string(), 1), (2, pool.to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_writes_are_attributed_to_the_referencing_program() {
+        let (program, pool) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let processed = BlockProcessor::new().process(100, &block(vec![transaction(program, &[pool], &[])]));
+
+        let attribution: Vec<_> = processed.batch.events.iter()
+            .map(|e| (e.account_pubkey == pool.to_string(), e.program_id.clone(), e.instruction_index))
+            .collect();
+        // The fee payer isn't passed to any instruction
+        assert_eq!(attribution, vec![(false, None, None), (true, Some(program.to_string()), Some(0))]);
+    }
+
+    #[test]
+    fn test_transactions_up_to_a_tip_from_its_signer_are_bundles() {
+        let [first, second, ..] = JITO_TIP_ACCOUNTS;
+        let (searcher, other_searcher) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let plain = || transaction(Pubkey::new_unique(), &[Pubkey::new_unique()], &[]);
+        let from = |payer| transaction_from(payer, Pubkey::new_unique(), &[Pubkey::new_unique()], &[]);
+        let processed = BlockProcessor::new().process(100, &block(vec![
+            plain(),
+            // Front-run, target, back-run with the tip
+            from(searcher),
+            plain(),
+            tipping_transaction(searcher, first),
+            // Tippers on their own, right before and after unrelated transactions
+            plain(),
+            tipping_transaction(Pubkey::new_unique(), second),
+            tipping_transaction(Pubkey::new_unique(), first),
+            // A bundle of one signer's transactions, with a single tip
+            from(other_searcher),
+            tipping_transaction(other_searcher, second),
+            plain(),
+        ]));
+
+        let bundled: BTreeMap<_, _> = processed.batch.events.iter()
+            .map(|e| (e.transaction_index, e.likely_bundle))
+            .collect();
+        assert_eq!(bundled.into_iter().collect::<Vec<_>>(), vec![
+            (0, false), (1, true), (2, true), (3, true), (4, false),
+            (5, false), (6, false), (7, true), (8, true), (9, false),
+        ]);
+        let tips: Vec<_> = processed.batch.events.iter()
+            .filter(|e| e.account_pubkey == first || e.account_pubkey == second)
+            .map(|e| (e.transaction_index, e.tip_lamports))
+            .collect();
+        assert_eq!(tips, vec![(3, 10_000), (5, 10_000), (6, 10_000), (8, 10_000)]);
+        assert!(processed.batch.events.iter().filter(|e| e.transaction_index == 9).all(|e| e.tip_lamports == 0));
+    }
+
+    #[test]
+    fn test_bundles_are_at_most_five_transactions() {
+        let searcher = Pubkey::new_unique();
+        let mut transactions: Vec<_> = (0..7)
+            .map(|_| transaction_from(searcher, Pubkey::new_unique(), &[Pubkey::new_unique()], &[]))
+            .collect();
+        transactions.push(tipping_transaction(searcher, JITO_TIP_ACCOUNTS[0]));
+        let processed = BlockProcessor::new().process(100, &block(transactions));
+
+        let bundled: BTreeSet<_> = processed.batch.events.iter()
+            .filter(|e| e.likely_bundle)
+            .map(|e| e.transaction_index)
+            .collect();
+        assert_eq!(bundled.into_iter().collect::<Vec<_>>(), vec![3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_vote_transactions_are_counted_but_not_tracked() {
+        let vote_program: Pubkey = VOTE_PROGRAM_ID.parse().unwrap();
+        let (program, pool, vote_account) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let block = block(vec![
+            transaction(vote_program, &[vote_account], &[]),
+            transaction(program, &[pool], &[]),
+        ]);
+
+        let processed = BlockProcessor::new().process(100, &block);
+        assert!(processed.batch.events.iter().all(|e| e.account_pubkey != vote_account.to_string()));
+        assert_eq!(processed.batch.events.len(), 2);
+        let summary = &processed.batch.summaries[0];
+        assert_eq!((summary.transaction_count, summary.vote_transaction_count), (2, 1));
+        assert_eq!(processed.batch.conflict_graphs[0].transaction_count, 1);
+
+        let processed = BlockProcessor::new().with_vote_transactions(true).process(100, &block);
+        assert!(processed.batch.events.iter().any(|e| e.account_pubkey == vote_account.to_string()));
+        assert_eq!(processed.batch.events.len(), 4);
+    }
+}
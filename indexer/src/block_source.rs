@@ -0,0 +1,313 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_transaction_status::UiConfirmedBlock;
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tracing::warn;
+
+use crate::block_processor::SLOT_DURATION_MS;
+
+use crate::config::{Config, IngestionMode};
+use crate::pipeline::{spawn_block_fetcher, spawn_slot_discovery, FetchedSlot};
+use crate::subscription::{spawn_subscription_discovery, SubscriptionKind};
+
+/// A slot delivered by a block source
+pub struct SourceBlock {
+    pub slot: u64,
+    /// `None` when the slot was skipped
+    pub block: Option<UiConfirmedBlock>,
+}
+
+/// Where blocks come from. Sources yield slots in ascending order.
+#[async_trait]
+pub trait BlockSource: Send {
+    /// The next slot, or `None` once the source is exhausted
+    async fn next_block(&mut self) -> Result<Option<SourceBlock>>;
+
+    /// Whether no further block is ready yet. Partial batches are flushed at this point.
+    fn is_idle(&self) -> bool;
+}
+
+/// Settings for following the tip over RPC
+#[derive(Debug, Clone)]
+pub struct RpcSourceOptions {
+    pub ingestion_mode: IngestionMode,
+    pub ws_endpoint: String,
+    pub poll_interval: Duration,
+    /// Number of `getBlock` requests in flight at once
+    pub fetch_concurrency: usize,
+    /// Bound of the channels between pipeline stages
+    pub pipeline_capacity: usize,
+}
+
+impl RpcSourceOptions {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            ingestion_mode: config.ingestion_mode,
+            ws_endpoint: config.ws_endpoint(),
+            poll_interval: Duration::from_millis(config.poll_interval_ms),
+            fetch_concurrency: config.fetch_concurrency.max(1),
+            pipeline_capacity: config.pipeline_capacity.max(1),
+        }
+    }
+}
+
+/// Follows the chain tip through the discovery and fetch stages of the pipeline,
+/// polling `getSlot` or subscribing over websocket depending on `ingestion_mode`.
+/// Dropping the source stops both stages.
+pub struct RpcBlockSource {
+    blocks: mpsc::Receiver<FetchedSlot>,
+}
+
+impl RpcBlockSource {
+    /// Start discovering and fetching slots after `start_after`
+    pub fn start(client: Arc<RpcClient>, options: &RpcSourceOptions, start_after: u64) -> Self {
+        let (slot_tx, slot_rx) = mpsc::channel(options.pipeline_capacity);
+        let (block_tx, block_rx) = mpsc::channel(options.pipeline_capacity);
+
+        let subscription = match options.ingestion_mode {
+            IngestionMode::Poll => None,
+            IngestionMode::SlotSubscribe => Some(SubscriptionKind::Slot),
+            IngestionMode::BlockSubscribe => Some(SubscriptionKind::Block),
+        };
+
+        // Both stages exit on their own once the channels they feed are dropped
+        match subscription {
+            Some(kind) => spawn_subscription_discovery(
+                client.clone(),
+                options.ws_endpoint.clone(),
+                kind,
+                start_after,
+                options.poll_interval,
+                slot_tx,
+            ),
+            None => spawn_slot_discovery(client.clone(), start_after, options.poll_interval, slot_tx),
+        };
+        spawn_block_fetcher(client, slot_rx, options.fetch_concurrency, block_tx);
+
+        Self { blocks: block_rx }
+    }
+}
+
+#[async_trait]
+impl BlockSource for RpcBlockSource {
+    async fn next_block(&mut self) -> Result<Option<SourceBlock>> {
+        match self.blocks.recv().await {
+            Some(fetched) => Ok(Some(SourceBlock { slot: fetched.slot, block: fetched.block? })),
+            None => Ok(None),
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.blocks.is_empty()
+    }
+}
+
+/// Replays a directory of `<slot>.json` or `<slot>.json.gz` files holding `getBlock`
+/// results, as written by `RecordingSource`. A file containing `null` marks a skipped
+/// slot.
+pub struct DirectoryBlockSource {
+    files: VecDeque<(u64, PathBuf)>,
+    /// Replay speed relative to the 400ms slot time, `None` to read as fast as possible
+    speed: Option<f64>,
+    /// Slot and instant of the previous block, to pace the next one
+    last_emitted: Option<(u64, Instant)>,
+}
+
+impl DirectoryBlockSource {
+    pub fn open(dir: &Path) -> Result<Self> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+            let path = entry?.path();
+            let slot = path.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix(".json.gz").or_else(|| name.strip_suffix(".json")))
+                .and_then(|stem| stem.parse::<u64>().ok());
+            if let Some(slot) = slot {
+                files.push((slot, path));
+            }
+        }
+        files.sort_unstable_by_key(|(slot, _)| *slot);
+
+        Ok(Self { files: files.into(), speed: None, last_emitted: None })
+    }
+
+    /// Emit blocks at the pace they were produced, `speed` times faster
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        self.speed = Some(speed).filter(|speed| *speed > 0.0);
+        self
+    }
+
+    async fn wait_for_slot(&mut self, slot: u64) {
+        if let (Some(speed), Some((last_slot, last_instant))) = (self.speed, self.last_emitted) {
+            let slots = slot.saturating_sub(last_slot) as f64;
+            let delay = Duration::from_secs_f64(slots * SLOT_DURATION_MS as f64 / 1000.0 / speed);
+            tokio::time::sleep_until(last_instant + delay).await;
+        }
+        self.last_emitted = Some((slot, Instant::now()));
+    }
+}
+
+#[async_trait]
+impl BlockSource for DirectoryBlockSource {
+    async fn next_block(&mut self) -> Result<Option<SourceBlock>> {
+        let Some((slot, path)) = self.files.pop_front() else {
+            return Ok(None);
+        };
+
+        let content = tokio::fs::read(&path).await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let block = if path.extension().is_some_and(|ext| ext == "gz") {
+            let mut json = Vec::new();
+            GzDecoder::new(content.as_slice()).read_to_end(&mut json)
+                .with_context(|| format!("Failed to decompress {}", path.display()))?;
+            serde_json::from_slice(&json)
+        } else {
+            serde_json::from_slice(&content)
+        }
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        self.wait_for_slot(slot).await;
+        Ok(Some(SourceBlock { slot, block }))
+    }
+
+    fn is_idle(&self) -> bool {
+        // A paced replay waits between blocks, so flush each one like at the tip
+        self.files.is_empty() || self.speed.is_some()
+    }
+}
+
+/// Passes blocks through from another source and saves each one as
+/// `<dir>/<slot>.json.gz`, for replay with `DirectoryBlockSource`. A failed write is
+/// logged and doesn't interrupt processing.
+pub struct RecordingSource<S> {
+    inner: S,
+    dir: PathBuf,
+}
+
+impl<S: BlockSource> RecordingSource<S> {
+    pub fn new(inner: S, dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        Ok(Self { inner, dir: dir.to_path_buf() })
+    }
+}
+
+#[async_trait]
+impl<S: BlockSource> BlockSource for RecordingSource<S> {
+    async fn next_block(&mut self) -> Result<Option<SourceBlock>> {
+        let next = self.inner.next_block().await?;
+
+        if let Some(source_block) = &next {
+            let path = self.dir.join(format!("{}.json.gz", source_block.slot));
+            let result = match serde_json::to_vec(&source_block.block) {
+                Ok(json) => tokio::task::spawn_blocking(move || write_compressed(&path, &json)).await
+                    .context("Recording task failed")
+                    .and_then(|result| result),
+                Err(e) => Err(e.into()),
+            };
+            if let Err(e) = result {
+                warn!("⚠️ Failed to record slot {}: {:#}", source_block.slot, e);
+            }
+        }
+
+        Ok(next)
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+}
+
+fn write_compressed(path: &Path, json: &[u8]) -> Result<()> {
+    // Write under a temporary name so a replay never sees a partial file
+    let partial = path.with_extension("gz.partial");
+    let mut encoder = GzEncoder::new(fs::File::create(&partial)?, Compression::default());
+    encoder.write_all(json)?;
+    encoder.finish()?;
+    fs::rename(&partial, path)?;
+    Ok(())
+}
+
+/// Yields blocks from memory, for tests
+#[cfg(test)]
+pub struct MemoryBlockSource {
+    blocks: VecDeque<SourceBlock>,
+}
+
+#[cfg(test)]
+impl MemoryBlockSource {
+    pub fn new(blocks: Vec<(u64, Option<UiConfirmedBlock>)>) -> Self {
+        Self {
+            blocks: blocks.into_iter().map(|(slot, block)| SourceBlock { slot, block }).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl BlockSource for MemoryBlockSource {
+    async fn next_block(&mut self) -> Result<Option<SourceBlock>> {
+        Ok(self.blocks.pop_front())
+    }
+
+    fn is_idle(&self) -> bool {
+        self.blocks.is_empty()
+    }
+}
+
+/// Recorded blocks shipped with the repo for end-to-end tests
+#[cfg(test)]
+pub fn fixture_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/blocks")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_directory_source_yields_slots_in_order() {
+        let mut source = DirectoryBlockSource::open(&fixture_dir()).unwrap();
+
+        let mut slots = Vec::new();
+        while let Some(block) = source.next_block().await.unwrap() {
+            slots.push((block.slot, block.block.is_some()));
+        }
+
+        assert_eq!(slots, vec![(300_000_000, true), (300_000_001, false), (300_000_002, true)]);
+        assert!(source.is_idle());
+    }
+
+    #[tokio::test]
+    async fn test_recorded_blocks_replay_at_speed() {
+        let fixture = fs::read(fixture_dir().join("300000000.json")).unwrap();
+        let block: UiConfirmedBlock = serde_json::from_slice(&fixture).unwrap();
+        let dir = std::env::temp_dir().join(format!("lock-indexer-record-{}", std::process::id()));
+
+        let memory = MemoryBlockSource::new(vec![(10, Some(block.clone())), (11, None), (12, Some(block.clone()))]);
+        let mut recorder = RecordingSource::new(memory, &dir).unwrap();
+        while recorder.next_block().await.unwrap().is_some() {}
+
+        // 2 slots apart at 4x speed: 800ms / 4
+        let started = Instant::now();
+        let mut replay = DirectoryBlockSource::open(&dir).unwrap().with_speed(4.0);
+        let mut replayed = Vec::new();
+        while let Some(source_block) = replay.next_block().await.unwrap() {
+            replayed.push((source_block.slot, source_block.block));
+        }
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        assert_eq!(replayed, vec![(10, Some(block.clone())), (11, None), (12, Some(block))]);
+    }
+}
@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(name = "indexer", about = "Solana write lock contention indexer")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Follow the chain tip (default)
+    Run,
+    /// Index a historical slot or time range
+    Backfill(BackfillArgs),
+    /// Re-index blocks recorded with RECORD_DIR
+    Replay(ReplayArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct BackfillArgs {
+    /// First slot to index
+    #[arg(long, requires = "to_slot", conflicts_with_all = ["from_time", "to_time"])]
+    pub from_slot: Option<u64>,
+
+    /// Last slot to index (inclusive)
+    #[arg(long, requires = "from_slot")]
+    pub to_slot: Option<u64>,
+
+    /// Start of the time range (RFC 3339, e.g. 2024-05-01T12:00:00Z)
+    #[arg(long, requires = "to_time", required_unless_present = "from_slot")]
+    pub from_time: Option<DateTime<Utc>>,
+
+    /// End of the time range (RFC 3339)
+    #[arg(long, requires = "from_time")]
+    pub to_time: Option<DateTime<Utc>>,
+
+    /// Number of blocks fetched concurrently
+    #[arg(long, default_value_t = 8)]
+    pub concurrency: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct ReplayArgs {
+    /// Directory of recorded `<slot>.json.gz` blocks
+    #[arg(long)]
+    pub dir: PathBuf,
+
+    /// Replay speed relative to real time (2 = twice as fast)
+    #[arg(long, default_value_t = 1.0, conflicts_with = "max_speed")]
+    pub speed: f64,
+
+    /// Process blocks as fast as possible
+    #[arg(long)]
+    pub max_speed: bool,
+}
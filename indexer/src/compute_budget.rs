@@ -0,0 +1,138 @@
+use solana_sdk::{compute_budget, message::VersionedMessage};
+
+/// Base fee charged per signature (lamports)
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Default CU limit granted per non-ComputeBudget instruction when no limit is requested
+const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+
+/// Hard cap on the CU limit a transaction can request
+const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+// Borsh discriminants of `ComputeBudgetInstruction`
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+
+/// Fee components of a single transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeBreakdown {
+    /// `SetComputeUnitPrice` value (0 when not set)
+    pub compute_unit_price_micro_lamports: u64,
+    /// Effective requested CU limit (explicit `SetComputeUnitLimit` or the runtime default)
+    pub compute_unit_limit: u32,
+    /// Signature fee
+    pub base_fee_lamports: u64,
+    /// CU price * CU limit, rounded up to whole lamports
+    pub priority_fee_lamports: u64,
+}
+
+/// Parse the ComputeBudget instructions of a message and derive its fee components
+pub fn fee_breakdown(message: &VersionedMessage) -> FeeBreakdown {
+    let keys = message.static_account_keys();
+    let mut unit_price: Option<u64> = None;
+    let mut unit_limit: Option<u32> = None;
+    let mut other_instructions: u32 = 0;
+
+    for ix in message.instructions() {
+        let is_compute_budget = keys
+            .get(ix.program_id_index as usize)
+            .map(|program| *program == compute_budget::id())
+            .unwrap_or(false);
+
+        if !is_compute_budget {
+            other_instructions += 1;
+            continue;
+        }
+
+        match ix.data.split_first() {
+            Some((&SET_COMPUTE_UNIT_LIMIT, rest)) => {
+                if let Some(bytes) = rest.get(..4) {
+                    unit_limit = Some(u32::from_le_bytes(bytes.try_into().unwrap()));
+                }
+            }
+            Some((&SET_COMPUTE_UNIT_PRICE, rest)) => {
+                if let Some(bytes) = rest.get(..8) {
+                    unit_price = Some(u64::from_le_bytes(bytes.try_into().unwrap()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let compute_unit_limit = unit_limit
+        .unwrap_or_else(|| other_instructions.saturating_mul(DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT))
+        .min(MAX_COMPUTE_UNIT_LIMIT);
+    let compute_unit_price_micro_lamports = unit_price.unwrap_or(0);
+
+    let priority_fee_lamports = (compute_unit_price_micro_lamports as u128 * compute_unit_limit as u128)
+        .div_ceil(MICRO_LAMPORTS_PER_LAMPORT) as u64;
+    let base_fee_lamports = message.header().num_required_signatures as u64 * LAMPORTS_PER_SIGNATURE;
+
+    FeeBreakdown {
+        compute_unit_price_micro_lamports,
+        compute_unit_limit,
+        base_fee_lamports,
+        priority_fee_lamports,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{
+        compute_budget::ComputeBudgetInstruction,
+        instruction::{AccountMeta, Instruction},
+        message::Message,
+        pubkey::Pubkey,
+    };
+
+    fn message(instructions: &[Instruction]) -> VersionedMessage {
+        let payer = Pubkey::new_unique();
+        VersionedMessage::Legacy(Message::new(instructions, Some(&payer)))
+    }
+
+    fn swap() -> Instruction {
+        Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[1, 2, 3],
+            vec![AccountMeta::new(Pubkey::new_unique(), false)],
+        )
+    }
+
+    #[test]
+    fn test_explicit_price_and_limit() {
+        let fees = fee_breakdown(&message(&[
+            ComputeBudgetInstruction::set_compute_unit_limit(300_000),
+            ComputeBudgetInstruction::set_compute_unit_price(25_000),
+            swap(),
+        ]));
+
+        assert_eq!(fees.compute_unit_price_micro_lamports, 25_000);
+        assert_eq!(fees.compute_unit_limit, 300_000);
+        assert_eq!(fees.base_fee_lamports, 5_000);
+        // 25_000 µL * 300_000 CU = 7_500 lamports
+        assert_eq!(fees.priority_fee_lamports, 7_500);
+    }
+
+    #[test]
+    fn test_default_limit_without_price() {
+        let fees = fee_breakdown(&message(&[swap(), swap()]));
+
+        assert_eq!(fees.compute_unit_price_micro_lamports, 0);
+        assert_eq!(fees.compute_unit_limit, 400_000);
+        assert_eq!(fees.priority_fee_lamports, 0);
+    }
+
+    #[test]
+    fn test_priority_fee_rounds_up() {
+        let fees = fee_breakdown(&message(&[
+            ComputeBudgetInstruction::set_compute_unit_price(1),
+            swap(),
+        ]));
+
+        // 1 µL * 200_000 CU = 0.2 lamports, charged as 1
+        assert_eq!(fees.priority_fee_lamports, 1);
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use crate::lock_detector::LockDetector;
+
+/// Shape of a block's transaction conflict graph, where two transactions are
+/// connected when they write-lock a common account
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConflictGraphStats {
+    pub transaction_count: u32,
+    /// Groups of transactions that conflict directly or through others
+    pub component_count: u32,
+    pub largest_component: u32,
+    /// Most transactions that have to run one after another: the longest path
+    /// through the block where each step waits on an earlier writer
+    pub longest_chain: u32,
+    /// Transactions per step of the longest chain: the speedup unlimited threads
+    /// would get over running the block serially
+    pub parallelism_factor: f32,
+    /// Account with the most writers, the one that serializes the most transactions
+    pub bottleneck_account: Option<String>,
+    pub bottleneck_writers: u32,
+}
+
+/// Build the conflict graph from the write locks tracked by `detector`
+pub fn analyze(detector: &LockDetector) -> ConflictGraphStats {
+    let transactions = detector.transactions();
+    let index: HashMap<&str, usize> = transactions.iter()
+        .enumerate()
+        .map(|(i, signature)| (signature.as_str(), i))
+        .collect();
+
+    // A writer waits on the previous writer of each of its accounts. Linking
+    // consecutive writers is enough for connectivity and chain length alike.
+    let mut waits_on: Vec<Vec<usize>> = vec![Vec::new(); transactions.len()];
+    let mut components = UnionFind::new(transactions.len());
+    let mut bottleneck: Option<(&str, usize)> = None;
+
+    for (account, writers) in detector.write_queues() {
+        let writers: Vec<usize> = writers.iter().filter_map(|sig| index.get(sig.as_str()).copied()).collect();
+        for pair in writers.windows(2) {
+            waits_on[pair[1]].push(pair[0]);
+            components.union(pair[0], pair[1]);
+        }
+
+        let is_new_max = bottleneck.is_none_or(|(top, count)| {
+            writers.len() > count || (writers.len() == count && account < top)
+        });
+        if is_new_max {
+            bottleneck = Some((account, writers.len()));
+        }
+    }
+
+    // Transactions are indexed in block order, so every dependency is already resolved
+    let mut chain = vec![0u32; transactions.len()];
+    for i in 0..transactions.len() {
+        chain[i] = 1 + waits_on[i].iter().map(|&j| chain[j]).max().unwrap_or(0);
+    }
+    let longest_chain = chain.iter().copied().max().unwrap_or(0);
+
+    let mut sizes: HashMap<usize, u32> = HashMap::new();
+    for i in 0..transactions.len() {
+        *sizes.entry(components.find(i)).or_default() += 1;
+    }
+
+    ConflictGraphStats {
+        transaction_count: transactions.len() as u32,
+        component_count: sizes.len() as u32,
+        largest_component: sizes.values().copied().max().unwrap_or(0),
+        longest_chain,
+        parallelism_factor: if longest_chain > 0 {
+            transactions.len() as f32 / longest_chain as f32
+        } else {
+            0.0
+        },
+        bottleneck_account: bottleneck.map(|(account, _)| account.to_string()),
+        bottleneck_writers: bottleneck.map_or(0, |(_, count)| count as u32),
+    }
+}
+
+struct UnionFind {
+    parent: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(size: usize) -> Self {
+        Self { parent: (0..size).collect() }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parent[b] = a;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_chains_and_components() {
+        let (pool, vault, other) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut detector = LockDetector::new();
+        detector.track_transaction("tx1", &[pool]);
+        detector.track_transaction("tx2", &[pool, vault]);
+        detector.track_transaction("tx3", &[vault]);
+        detector.track_transaction("tx4", &[other]);
+        detector.track_transaction("tx5", &[pool]);
+
+        let stats = analyze(&detector);
+
+        // tx1 -> tx2 -> tx3 and tx2 -> tx5; tx4 runs on its own
+        assert_eq!(stats.transaction_count, 5);
+        assert_eq!(stats.component_count, 2);
+        assert_eq!(stats.largest_component, 4);
+        assert_eq!(stats.longest_chain, 3);
+        assert_eq!(stats.parallelism_factor, 5.0 / 3.0);
+        assert_eq!(stats.bottleneck_account, Some(pool.to_string()));
+        assert_eq!(stats.bottleneck_writers, 3);
+    }
+
+    #[test]
+    fn test_empty_block() {
+        let stats = analyze(&LockDetector::new());
+        assert_eq!(stats.transaction_count, 0);
+        assert_eq!(stats.longest_chain, 0);
+        assert_eq!(stats.parallelism_factor, 0.0);
+        assert_eq!(stats.bottleneck_account, None);
+    }
+}
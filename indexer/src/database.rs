@@ -1,22 +1,463 @@
 use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashSet;
 use chrono::{DateTime, Utc};
 use deadpool_postgres::{Manager, ManagerConfig, Pool, RecyclingMethod};
-use tokio_postgres::NoTls;
+use futures::pin_mut;
+use tokio_postgres::binary_copy::BinaryCopyInWriter;
+use tokio_postgres::types::{ToSql, Type};
+use tokio_postgres::{NoTls, Transaction};
 use tracing::info;
 
+use crate::account_enricher::AccountType;
+use crate::account_keys::AccountSource;
+use crate::failure_reason::FailureReason;
+
 #[derive(Debug, Clone)]
 pub struct WriteLockEvent {
+    /// Block time (estimated from slot timing when the RPC has none)
     pub time: DateTime<Utc>,
+    pub time_estimated: bool,
+    /// Wall-clock time the indexer processed the block
+    pub ingested_at: DateTime<Utc>,
     pub slot: i64,
+    /// Position of the transaction within its block
+    pub transaction_index: i32,
     pub account_pubkey: String,
+    pub account_source: AccountSource,
+    /// Innermost program referencing the account, CPIs included (see `write_attribution`)
     pub program_id: Option<String>,
+    /// Program of the top-level instruction the account was reached from
+    pub invoking_program_id: Option<String>,
+    /// Index of that top-level instruction
+    pub instruction_index: Option<i32>,
     pub transaction_signature: String,
-    pub success: bool,
+    /// First signer, who paid for the transaction
+    pub fee_payer: String,
+    /// Mint of the account when it's a token account
+    pub mint: Option<String>,
+    /// `None` when the RPC returned no status for the transaction
+    pub success: Option<bool>,
+    /// Classified cause when the transaction failed
+    pub failure_reason: Option<FailureReason>,
+    /// Top-level instruction that failed
+    pub error_instruction_index: Option<i32>,
+    /// `InstructionError::Custom` code
+    pub error_code: Option<i64>,
+    /// Transactions write-locking the account in this slot (queue size)
     pub lock_contention_score: f32,
+    /// 1-based position of the transaction among the slot's writers of the account
+    pub queue_position: i32,
+    /// Transactions read-locking the account in this slot. The scheduler can't run
+    /// them alongside any of its writers.
+    pub read_conflicts: i32,
+    /// ComputeBudget `SetComputeUnitPrice` (micro-lamports per CU)
+    pub compute_unit_price_micro_lamports: Option<i64>,
+    /// Requested CU limit (explicit or runtime default)
+    pub compute_unit_limit: Option<i32>,
+    /// Signature fee, excluded from the priority fee
+    pub base_fee_lamports: Option<i64>,
+    /// CU price * CU limit
     pub priority_fee_lamports: Option<i64>,
     pub compute_units_consumed: Option<i32>,
+    /// CU consumed by all writers of the account in this slot, counted against the
+    /// per-account block limit
+    pub account_compute_units: i64,
+    /// Lamports transferred to Jito tip accounts (see `jito_tips`)
+    pub tip_lamports: i64,
+    /// Part of a run of transactions that ends in a tip and starts with one sharing
+    /// a signer with the tipper (see `MAX_BUNDLE_TRANSACTIONS`)
+    pub likely_bundle: bool,
+}
+
+/// A read lock on an account that is also write-locked in the same slot. Reads of
+/// accounts nobody writes don't block anything and aren't recorded.
+#[derive(Debug, Clone)]
+pub struct ReadLockEvent {
+    pub time: DateTime<Utc>,
+    pub time_estimated: bool,
+    pub ingested_at: DateTime<Utc>,
+    pub slot: i64,
+    pub transaction_index: i32,
+    pub account_pubkey: String,
+    pub account_source: AccountSource,
+    pub program_id: Option<String>,
+    pub transaction_signature: String,
+    pub success: Option<bool>,
+    /// Transactions write-locking the account in this slot
+    pub writer_count: i32,
+}
+
+/// Conflict graph metrics of one block (see `conflict_graph::ConflictGraphStats`)
+#[derive(Debug, Clone)]
+pub struct SlotConflictGraph {
+    pub time: DateTime<Utc>,
+    pub slot: i64,
+    pub transaction_count: i32,
+    pub component_count: i32,
+    pub largest_component: i32,
+    pub longest_chain: i32,
+    pub parallelism_factor: f32,
+    pub bottleneck_account: Option<String>,
+    pub bottleneck_writers: i32,
+}
+
+/// Block-wide totals of one slot. Skipped slots get a row too, with `skipped` set,
+/// zero totals and an estimated time.
+#[derive(Debug, Clone)]
+pub struct SlotSummary {
+    pub time: DateTime<Utc>,
+    pub time_estimated: bool,
+    pub ingested_at: DateTime<Utc>,
+    pub slot: i64,
+    pub skipped: bool,
+    pub parent_slot: Option<i64>,
+    /// Validator scheduled to produce the slot, when the schedule is available
+    pub leader: Option<String>,
+    pub transaction_count: i32,
+    pub vote_transaction_count: i32,
+    pub failed_transaction_count: i32,
+    pub total_compute_units: i64,
+    /// Sum of the fees charged (signature and priority fees)
+    pub total_fees_lamports: i64,
+    pub total_priority_fees_lamports: i64,
+    /// `total_compute_units` as a percentage of the block CU limit
+    pub block_fullness_pct: f32,
+}
+
+/// What the enricher found out about one account
+#[derive(Debug, Clone)]
+pub struct AccountMetadata {
+    pub pubkey: String,
+    /// Owner program
+    pub program_id: String,
+    /// Label from `known_programs`; an existing label is kept when `None`
+    pub label: Option<&'static str>,
+    pub data_len: i64,
+    pub executable: bool,
+    pub account_type: AccountType,
+}
+
+/// Rows of one or more whole slots, committed together
+#[derive(Debug, Clone, Default)]
+pub struct SlotBatch {
+    pub events: Vec<WriteLockEvent>,
+    pub reads: Vec<ReadLockEvent>,
+    pub conflict_graphs: Vec<SlotConflictGraph>,
+    pub summaries: Vec<SlotSummary>,
+}
+
+impl SlotBatch {
+    /// Number of rows across all tables
+    pub fn len(&self) -> usize {
+        self.events.len() + self.reads.len() + self.conflict_graphs.len() + self.summaries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Slots with rows in the batch; every processed slot has a summary
+    pub fn slots(&self) -> Vec<i64> {
+        let slots: HashSet<i64> = self.events.iter().map(|e| e.slot)
+            .chain(self.reads.iter().map(|r| r.slot))
+            .chain(self.conflict_graphs.iter().map(|g| g.slot))
+            .chain(self.summaries.iter().map(|s| s.slot))
+            .collect();
+        let mut slots: Vec<i64> = slots.into_iter().collect();
+        slots.sort_unstable();
+        slots
+    }
+
+    pub fn append(&mut self, other: SlotBatch) {
+        self.events.extend(other.events);
+        self.reads.extend(other.reads);
+        self.conflict_graphs.extend(other.conflict_graphs);
+        self.summaries.extend(other.summaries);
+    }
+}
+
+/// A hypertable written with binary `COPY` into a temp staging table, then merged
+/// on its natural key
+struct CopyTable {
+    name: &'static str,
+    /// Columns in COPY order, matching the table's `*_values` function
+    columns: &'static [(&'static str, Type)],
+    key: &'static [&'static str],
+}
+
+/// Key per migrations/006_event_natural_key.sql
+const WRITE_LOCK_EVENTS: CopyTable = CopyTable {
+    name: "write_lock_events",
+    columns: &[
+        ("time", Type::TIMESTAMPTZ),
+        ("time_estimated", Type::BOOL),
+        ("ingested_at", Type::TIMESTAMPTZ),
+        ("slot", Type::INT8),
+        ("transaction_index", Type::INT4),
+        ("account_pubkey", Type::TEXT),
+        ("account_source", Type::TEXT),
+        ("program_id", Type::TEXT),
+        ("invoking_program_id", Type::TEXT),
+        ("instruction_index", Type::INT4),
+        ("transaction_signature", Type::TEXT),
+        ("fee_payer", Type::TEXT),
+        ("mint", Type::TEXT),
+        ("success", Type::BOOL),
+        ("failure_reason", Type::TEXT),
+        ("error_instruction_index", Type::INT4),
+        ("error_code", Type::INT8),
+        ("lock_contention_score", Type::FLOAT4),
+        ("queue_position", Type::INT4),
+        ("read_conflicts", Type::INT4),
+        ("compute_unit_price_micro_lamports", Type::INT8),
+        ("compute_unit_limit", Type::INT4),
+        ("base_fee_lamports", Type::INT8),
+        ("priority_fee_lamports", Type::INT8),
+        ("compute_units_consumed", Type::INT4),
+        ("account_compute_units", Type::INT8),
+        ("tip_lamports", Type::INT8),
+        ("likely_bundle", Type::BOOL),
+    ],
+    key: &["slot", "transaction_signature", "account_pubkey", "time"],
+};
+
+/// See migrations/008_read_locks.sql
+const READ_LOCK_EVENTS: CopyTable = CopyTable {
+    name: "read_lock_events",
+    columns: &[
+        ("time", Type::TIMESTAMPTZ),
+        ("time_estimated", Type::BOOL),
+        ("ingested_at", Type::TIMESTAMPTZ),
+        ("slot", Type::INT8),
+        ("transaction_index", Type::INT4),
+        ("account_pubkey", Type::TEXT),
+        ("account_source", Type::TEXT),
+        ("program_id", Type::TEXT),
+        ("transaction_signature", Type::TEXT),
+        ("success", Type::BOOL),
+        ("writer_count", Type::INT4),
+    ],
+    key: &["slot", "transaction_signature", "account_pubkey", "time"],
+};
+
+/// See migrations/009_slot_conflict_graphs.sql
+const SLOT_CONFLICT_GRAPHS: CopyTable = CopyTable {
+    name: "slot_conflict_graphs",
+    columns: &[
+        ("time", Type::TIMESTAMPTZ),
+        ("slot", Type::INT8),
+        ("transaction_count", Type::INT4),
+        ("component_count", Type::INT4),
+        ("largest_component", Type::INT4),
+        ("longest_chain", Type::INT4),
+        ("parallelism_factor", Type::FLOAT4),
+        ("bottleneck_account", Type::TEXT),
+        ("bottleneck_writers", Type::INT4),
+    ],
+    key: &["slot", "time"],
+};
+
+/// See migrations/011_slot_summaries.sql
+const SLOT_SUMMARIES: CopyTable = CopyTable {
+    name: "slot_summaries",
+    columns: &[
+        ("time", Type::TIMESTAMPTZ),
+        ("time_estimated", Type::BOOL),
+        ("ingested_at", Type::TIMESTAMPTZ),
+        ("slot", Type::INT8),
+        ("skipped", Type::BOOL),
+        ("parent_slot", Type::INT8),
+        ("leader", Type::TEXT),
+        ("transaction_count", Type::INT4),
+        ("vote_transaction_count", Type::INT4),
+        ("failed_transaction_count", Type::INT4),
+        ("total_compute_units", Type::INT8),
+        ("total_fees_lamports", Type::INT8),
+        ("total_priority_fees_lamports", Type::INT8),
+        ("block_fullness_pct", Type::FLOAT4),
+    ],
+    key: &["slot", "time"],
+};
+
+fn event_values(event: &WriteLockEvent) -> [&(dyn ToSql + Sync); 28] {
+    [
+        &event.time,
+        &event.time_estimated,
+        &event.ingested_at,
+        &event.slot,
+        &event.transaction_index,
+        &event.account_pubkey,
+        account_source_sql(event.account_source),
+        &event.program_id,
+        &event.invoking_program_id,
+        &event.instruction_index,
+        &event.transaction_signature,
+        &event.fee_payer,
+        &event.mint,
+        &event.success,
+        failure_reason_sql(event.failure_reason),
+        &event.error_instruction_index,
+        &event.error_code,
+        &event.lock_contention_score,
+        &event.queue_position,
+        &event.read_conflicts,
+        &event.compute_unit_price_micro_lamports,
+        &event.compute_unit_limit,
+        &event.base_fee_lamports,
+        &event.priority_fee_lamports,
+        &event.compute_units_consumed,
+        &event.account_compute_units,
+        &event.tip_lamports,
+        &event.likely_bundle,
+    ]
 }
 
+fn read_values(read: &ReadLockEvent) -> [&(dyn ToSql + Sync); 11] {
+    [
+        &read.time,
+        &read.time_estimated,
+        &read.ingested_at,
+        &read.slot,
+        &read.transaction_index,
+        &read.account_pubkey,
+        account_source_sql(read.account_source),
+        &read.program_id,
+        &read.transaction_signature,
+        &read.success,
+        &read.writer_count,
+    ]
+}
+
+fn conflict_graph_values(graph: &SlotConflictGraph) -> [&(dyn ToSql + Sync); 9] {
+    [
+        &graph.time,
+        &graph.slot,
+        &graph.transaction_count,
+        &graph.component_count,
+        &graph.largest_component,
+        &graph.longest_chain,
+        &graph.parallelism_factor,
+        &graph.bottleneck_account,
+        &graph.bottleneck_writers,
+    ]
+}
+
+fn summary_values(summary: &SlotSummary) -> [&(dyn ToSql + Sync); 14] {
+    [
+        &summary.time,
+        &summary.time_estimated,
+        &summary.ingested_at,
+        &summary.slot,
+        &summary.skipped,
+        &summary.parent_slot,
+        &summary.leader,
+        &summary.transaction_count,
+        &summary.vote_transaction_count,
+        &summary.failed_transaction_count,
+        &summary.total_compute_units,
+        &summary.total_fees_lamports,
+        &summary.total_priority_fees_lamports,
+        &summary.block_fullness_pct,
+    ]
+}
+
+/// Stored value of `account_source` ('static' or 'lookup_table')
+fn account_source_sql(source: AccountSource) -> &'static (dyn ToSql + Sync) {
+    match source {
+        AccountSource::Static => &"static",
+        AccountSource::LookupTable => &"lookup_table",
+    }
+}
+
+/// Stored value of `failure_reason`
+fn failure_reason_sql(reason: Option<FailureReason>) -> &'static (dyn ToSql + Sync) {
+    match reason {
+        None => &None::<&str>,
+        Some(FailureReason::Slippage) => &"slippage",
+        Some(FailureReason::StaleOracle) => &"stale_oracle",
+        Some(FailureReason::ComputeBudgetExceeded) => &"compute_budget_exceeded",
+        Some(FailureReason::InsufficientFunds) => &"insufficient_funds",
+        Some(FailureReason::CustomError) => &"custom_error",
+        Some(FailureReason::InstructionError) => &"instruction_error",
+        Some(FailureReason::TransactionError) => &"transaction_error",
+    }
+}
+
+fn account_type_sql(account_type: AccountType) -> &'static str {
+    match account_type {
+        AccountType::AmmPool => "amm_pool",
+        AccountType::OrderbookMarket => "orderbook_market",
+        AccountType::Oracle => "oracle",
+        AccountType::TokenAccount => "token_account",
+        AccountType::Mint => "mint",
+        AccountType::Wallet => "wallet",
+        AccountType::Program => "program",
+        AccountType::Other => "other",
+    }
+}
+
+impl CopyTable {
+    fn column_list(&self) -> String {
+        self.columns.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ")
+    }
+
+    /// Replace the rows of `slots` with `rows` within `tx`
+    async fn upsert<T, const N: usize>(
+        &self,
+        tx: &Transaction<'_>,
+        slots: &[i64],
+        rows: &[T],
+        values: fn(&T) -> [&(dyn ToSql + Sync); N],
+    ) -> Result<()> {
+        // A slot written again can come out with fewer rows (vote filter, changed
+        // attribution) or a different estimated time, so drop everything it had
+        tx.execute(&format!("DELETE FROM {} WHERE slot = ANY($1)", self.name), &[&slots]).await?;
+
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let columns: Vec<String> = self.columns.iter()
+            .map(|(name, ty)| format!("{} {}", name, ty.name()))
+            .collect();
+        tx.batch_execute(&format!(
+            "CREATE TEMP TABLE IF NOT EXISTS {}_staging ({}) ON COMMIT DELETE ROWS",
+            self.name,
+            columns.join(", ")
+        )).await?;
+
+        let copy_sql = format!("COPY {}_staging ({}) FROM STDIN (FORMAT binary)", self.name, self.column_list());
+        let sink = tx.copy_in(copy_sql.as_str()).await?;
+        let types: Vec<Type> = self.columns.iter().map(|(_, ty)| ty.clone()).collect();
+        let writer = BinaryCopyInWriter::new(sink, &types);
+        pin_mut!(writer);
+        for row in rows {
+            writer.as_mut().write(&values(row)).await?;
+        }
+        writer.finish().await?;
+
+        // Only conflicts with a concurrent writer of the same slot are left to merge
+        let updates: Vec<String> = self.columns.iter()
+            .map(|(name, _)| *name)
+            .filter(|name| !self.key.contains(name))
+            .map(|name| format!("{0} = EXCLUDED.{0}", name))
+            .collect();
+        let merge_sql = format!(
+            "INSERT INTO {table} ({columns}) SELECT {columns} FROM {table}_staging \
+             ON CONFLICT ({key}) DO UPDATE SET {updates}",
+            table = self.name,
+            columns = self.column_list(),
+            key = self.key.join(", "),
+            updates = updates.join(", "),
+        );
+        tx.execute(merge_sql.as_str(), &[]).await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
 pub struct Database {
     pool: Pool,
 }
@@ -38,66 +479,346 @@ impl Database {
 
         Ok(Self { pool })
     }
+}
 
-    pub async fn insert_events(&self, events: &[WriteLockEvent]) -> Result<()> {
-        if events.is_empty() {
-            return Ok(());
-        }
+/// Destination for processed slots
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    /// Last fully committed slot recorded under a checkpoint name
+    async fn get_checkpoint(&self, name: &str) -> Result<Option<u64>>;
+
+    /// Slots in `[from_slot, to_slot]` that were already processed
+    async fn indexed_slots(&self, from_slot: u64, to_slot: u64) -> Result<HashSet<u64>>;
+
+    /// Persist the rows of whole slots and advance `checkpoint` (if any) to `through_slot`
+    async fn commit_slots(
+        &self,
+        checkpoint: Option<&str>,
+        through_slot: u64,
+        batch: &SlotBatch,
+    ) -> Result<()>;
+
+    /// Store looked-up account metadata, replacing what was known before
+    async fn upsert_account_metadata(&self, accounts: &[AccountMetadata]) -> Result<()>;
 
+    /// Accounts among `pubkeys` whose metadata was stored at or after `since`
+    async fn enriched_since(&self, pubkeys: &[String], since: DateTime<Utc>) -> Result<HashSet<String>>;
+}
+
+#[async_trait]
+impl EventSink for Database {
+    /// Read the last fully committed slot recorded under a checkpoint name
+    async fn get_checkpoint(&self, name: &str) -> Result<Option<u64>> {
         let client = self.pool.get().await?;
 
-        for event in events {
-            client
-                .execute(
-                    r#"
-                    INSERT INTO write_lock_events (
-                        time, slot, account_pubkey, program_id, transaction_signature,
-                        success, lock_contention_score, priority_fee_lamports, compute_units_consumed
-                    )
-                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-                    "#,
-                    &[
-                        &event.time,
-                        &event.slot,
-                        &event.account_pubkey,
-                        &event.program_id,
-                        &event.transaction_signature,
-                        &event.success,
-                        &event.lock_contention_score,
-                        &event.priority_fee_lamports,
-                        &event.compute_units_consumed,
-                    ],
-                )
-                .await?;
-        }
+        let row = client
+            .query_opt(
+                "SELECT slot FROM indexer_checkpoints WHERE name = $1",
+                &[&name],
+            )
+            .await?;
 
-        Ok(())
+        Ok(row.map(|r| r.get::<_, i64>("slot") as u64))
     }
 
-    #[allow(dead_code)]
-    pub async fn upsert_account_metadata(
+    /// Slots in `[from_slot, to_slot]` with a summary, or with events if they were
+    /// indexed before summaries existed
+    async fn indexed_slots(&self, from_slot: u64, to_slot: u64) -> Result<HashSet<u64>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                r#"
+                SELECT slot FROM slot_summaries WHERE slot BETWEEN $1 AND $2
+                UNION
+                SELECT slot FROM write_lock_events WHERE slot BETWEEN $1 AND $2
+                "#,
+                &[&(from_slot as i64), &(to_slot as i64)],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|r| r.get::<_, i64>("slot") as u64).collect())
+    }
+
+    /// Upsert a batch of whole slots and advance the checkpoint (if any) to `through_slot`
+    /// in one transaction, so the checkpoint never points past data that isn't in the
+    /// table and a replayed slot replaces its rows instead of adding to them.
+    ///
+    /// Rows are streamed into temp staging tables with binary `COPY` and merged from
+    /// there, which is one round trip per table and batch instead of one per row.
+    async fn commit_slots(
         &self,
-        pubkey: &str,
-        program_id: Option<&str>,
-        label: Option<&str>,
+        checkpoint: Option<&str>,
+        through_slot: u64,
+        batch: &SlotBatch,
     ) -> Result<()> {
-        let client = self.pool.get().await?;
-        
-        client
-            .execute(
+        let mut client = self.pool.get().await?;
+        let tx = client.transaction().await?;
+
+        let slots = batch.slots();
+        WRITE_LOCK_EVENTS.upsert(&tx, &slots, &batch.events, event_values).await?;
+        READ_LOCK_EVENTS.upsert(&tx, &slots, &batch.reads, read_values).await?;
+        SLOT_CONFLICT_GRAPHS.upsert(&tx, &slots, &batch.conflict_graphs, conflict_graph_values).await?;
+        SLOT_SUMMARIES.upsert(&tx, &slots, &batch.summaries, summary_values).await?;
+
+        if let Some(checkpoint) = checkpoint {
+            tx.execute(
                 r#"
-                INSERT INTO account_metadata (pubkey, program_id, label, last_seen)
-                VALUES ($1, $2, $3, NOW())
+                INSERT INTO indexer_checkpoints (name, slot, updated_at)
+                VALUES ($1, $2, NOW())
+                ON CONFLICT (name)
+                DO UPDATE SET slot = EXCLUDED.slot, updated_at = NOW()
+                "#,
+                &[&checkpoint, &(through_slot as i64)],
+            )
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn upsert_account_metadata(&self, accounts: &[AccountMetadata]) -> Result<()> {
+        let mut client = self.pool.get().await?;
+        let tx = client.transaction().await?;
+
+        let statement = tx
+            .prepare(
+                r#"
+                INSERT INTO account_metadata
+                    (pubkey, program_id, label, data_len, executable, account_type, enriched_at, last_seen)
+                VALUES ($1, $2, $3, $4, $5, $6, NOW(), NOW())
                 ON CONFLICT (pubkey)
                 DO UPDATE SET
-                    program_id = COALESCE($2, account_metadata.program_id),
-                    label = COALESCE($3, account_metadata.label),
+                    program_id = EXCLUDED.program_id,
+                    label = COALESCE(EXCLUDED.label, account_metadata.label),
+                    data_len = EXCLUDED.data_len,
+                    executable = EXCLUDED.executable,
+                    account_type = EXCLUDED.account_type,
+                    enriched_at = NOW(),
                     last_seen = NOW()
                 "#,
-                &[&pubkey, &program_id, &label],
             )
             .await?;
 
+        for account in accounts {
+            tx.execute(&statement, &[
+                &account.pubkey,
+                &account.program_id,
+                &account.label,
+                &account.data_len,
+                &account.executable,
+                &account_type_sql(account.account_type),
+            ])
+            .await?;
+        }
+
+        tx.commit().await?;
         Ok(())
     }
+
+    async fn enriched_since(&self, pubkeys: &[String], since: DateTime<Utc>) -> Result<HashSet<String>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT pubkey FROM account_metadata WHERE pubkey = ANY($1) AND enriched_at >= $2",
+                &[&pubkeys, &since],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|r| r.get("pubkey")).collect())
+    }
+}
+
+/// Keeps committed events in memory, for tests that run without Postgres.
+/// Committing a slot again replaces its rows, as `Database` does.
+#[cfg(test)]
+#[derive(Default)]
+pub struct MemorySink {
+    pub events: std::sync::Mutex<Vec<WriteLockEvent>>,
+    pub reads: std::sync::Mutex<Vec<ReadLockEvent>>,
+    pub conflict_graphs: std::sync::Mutex<Vec<SlotConflictGraph>>,
+    pub summaries: std::sync::Mutex<Vec<SlotSummary>>,
+    pub checkpoints: std::sync::Mutex<std::collections::HashMap<String, u64>>,
+    pub metadata: std::sync::Mutex<Vec<AccountMetadata>>,
+    pub enriched_at: std::sync::Mutex<std::collections::HashMap<String, DateTime<Utc>>>,
+}
+
+#[cfg(test)]
+#[async_trait]
+impl EventSink for MemorySink {
+    async fn get_checkpoint(&self, name: &str) -> Result<Option<u64>> {
+        Ok(self.checkpoints.lock().unwrap().get(name).copied())
+    }
+
+    async fn indexed_slots(&self, from_slot: u64, to_slot: u64) -> Result<HashSet<u64>> {
+        Ok(self.summaries.lock().unwrap().iter()
+            .map(|s| s.slot as u64)
+            .filter(|slot| (from_slot..=to_slot).contains(slot))
+            .collect())
+    }
+
+    async fn commit_slots(
+        &self,
+        checkpoint: Option<&str>,
+        through_slot: u64,
+        batch: &SlotBatch,
+    ) -> Result<()> {
+        // Like the upsert, a committed slot replaces the rows it had before
+        let slots: HashSet<i64> = batch.slots().into_iter().collect();
+        fn replace<T: Clone>(rows: &std::sync::Mutex<Vec<T>>, new: &[T], slots: &HashSet<i64>, slot: fn(&T) -> i64) {
+            let mut rows = rows.lock().unwrap();
+            rows.retain(|row| !slots.contains(&slot(row)));
+            rows.extend_from_slice(new);
+        }
+        replace(&self.events, &batch.events, &slots, |e| e.slot);
+        replace(&self.reads, &batch.reads, &slots, |r| r.slot);
+        replace(&self.conflict_graphs, &batch.conflict_graphs, &slots, |g| g.slot);
+        replace(&self.summaries, &batch.summaries, &slots, |s| s.slot);
+        if let Some(checkpoint) = checkpoint {
+            self.checkpoints.lock().unwrap().insert(checkpoint.to_string(), through_slot);
+        }
+        Ok(())
+    }
+
+    async fn upsert_account_metadata(&self, accounts: &[AccountMetadata]) -> Result<()> {
+        self.metadata.lock().unwrap().extend_from_slice(accounts);
+        self.enriched_at.lock().unwrap()
+            .extend(accounts.iter().map(|account| (account.pubkey.clone(), Utc::now())));
+        Ok(())
+    }
+
+    async fn enriched_since(&self, pubkeys: &[String], since: DateTime<Utc>) -> Result<HashSet<String>> {
+        let enriched_at = self.enriched_at.lock().unwrap();
+        Ok(pubkeys.iter()
+            .filter(|pubkey| enriched_at.get(*pubkey).is_some_and(|at| *at >= since))
+            .cloned()
+            .collect())
+    }
+}
+
+/// These run against a migrated database and are skipped unless `TEST_DATABASE_URL` is set
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block_processor::BlockProcessor;
+    use crate::block_source::{fixture_dir, BlockSource, DirectoryBlockSource};
+    use std::time::{Duration, Instant};
+
+    /// Far above mainnet, so test rows never mix with indexed ones
+    const TEST_SLOT: i64 = 9_000_000_000;
+
+    async fn test_database() -> Option<Database> {
+        let url = std::env::var("TEST_DATABASE_URL").ok()?;
+        Some(Database::new(&url).await.unwrap())
+    }
+
+    /// The first fixture block, processed and moved to `slot`
+    async fn fixture_batch(slot: i64) -> SlotBatch {
+        let mut source = DirectoryBlockSource::open(&fixture_dir()).unwrap();
+        let block = source.next_block().await.unwrap().unwrap();
+        let mut batch = BlockProcessor::new().process(block.slot, block.block.as_ref().unwrap()).batch;
+        batch.events.iter_mut().for_each(|e| e.slot = slot);
+        batch.reads.iter_mut().for_each(|r| r.slot = slot);
+        batch.conflict_graphs.iter_mut().for_each(|g| g.slot = slot);
+        batch.summaries.iter_mut().for_each(|s| s.slot = slot);
+        batch
+    }
+
+    /// Rows per table for slots in `[from_slot, to_slot]`
+    async fn row_counts(db: &Database, from_slot: i64, to_slot: i64) -> [i64; 4] {
+        let client = db.pool.get().await.unwrap();
+        let mut counts = [0; 4];
+        for (count, table) in counts.iter_mut().zip(["write_lock_events", "read_lock_events", "slot_conflict_graphs", "slot_summaries"]) {
+            let sql = format!("SELECT COUNT(*) FROM {} WHERE slot BETWEEN $1 AND $2", table);
+            *count = client.query_one(sql.as_str(), &[&from_slot, &to_slot]).await.unwrap().get(0);
+        }
+        counts
+    }
+
+    async fn delete_slots(db: &Database, from_slot: i64, to_slot: i64) {
+        let client = db.pool.get().await.unwrap();
+        for table in ["write_lock_events", "read_lock_events", "slot_conflict_graphs", "slot_summaries"] {
+            let sql = format!("DELETE FROM {} WHERE slot BETWEEN $1 AND $2", table);
+            client.execute(sql.as_str(), &[&from_slot, &to_slot]).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recommitted_slot_replaces_its_rows() {
+        let Some(db) = test_database().await else { return };
+        let slot = TEST_SLOT;
+        delete_slots(&db, slot, slot).await;
+
+        let batch = fixture_batch(slot).await;
+        db.commit_slots(None, slot as u64, &batch).await.unwrap();
+        assert_eq!(row_counts(&db, slot, slot).await, [
+            batch.events.len() as i64,
+            batch.reads.len() as i64,
+            1,
+            1,
+        ]);
+
+        // Fewer rows on the second run, under a different estimated block time
+        let mut rerun = batch.clone();
+        rerun.events.truncate(1);
+        rerun.reads.clear();
+        let time = batch.summaries[0].time + chrono::Duration::milliseconds(400);
+        rerun.events.iter_mut().for_each(|e| e.time = time);
+        rerun.conflict_graphs.iter_mut().for_each(|g| g.time = time);
+        rerun.summaries.iter_mut().for_each(|s| s.time = time);
+        db.commit_slots(None, slot as u64, &rerun).await.unwrap();
+        assert_eq!(row_counts(&db, slot, slot).await, [1, 0, 1, 1]);
+
+        delete_slots(&db, slot, slot).await;
+    }
+
+    #[tokio::test]
+    async fn test_commits_keep_up_with_the_cluster() {
+        let Some(db) = test_database().await else { return };
+        const SLOTS: i64 = 50;
+        const SLOTS_PER_BATCH: i64 = 10;
+        // Roughly a busy mainnet block
+        const TRANSACTIONS_PER_SLOT: usize = 1_000;
+        let (from_slot, to_slot) = (TEST_SLOT + 1_000, TEST_SLOT + 1_000 + SLOTS - 1);
+        delete_slots(&db, from_slot, to_slot).await;
+
+        let template = fixture_batch(0).await;
+        let copies = TRANSACTIONS_PER_SLOT / template.summaries[0].transaction_count.max(1) as usize;
+        let mut batches = Vec::new();
+        for first in (from_slot..=to_slot).step_by(SLOTS_PER_BATCH as usize) {
+            let mut batch = SlotBatch::default();
+            for slot in first..first + SLOTS_PER_BATCH {
+                let mut slot_batch = fixture_batch(slot).await;
+                let (events, reads) = (slot_batch.events.clone(), slot_batch.reads.clone());
+                for copy in 1..copies {
+                    slot_batch.events.extend(events.iter().cloned().map(|mut e| {
+                        e.transaction_signature = format!("{}-{}", e.transaction_signature, copy);
+                        e
+                    }));
+                    slot_batch.reads.extend(reads.iter().cloned().map(|mut r| {
+                        r.transaction_signature = format!("{}-{}", r.transaction_signature, copy);
+                        r
+                    }));
+                }
+                batch.append(slot_batch);
+            }
+            batches.push(batch);
+        }
+        let rows: usize = batches.iter().map(SlotBatch::len).sum();
+
+        let started = Instant::now();
+        for batch in &batches {
+            db.commit_slots(None, 0, batch).await.unwrap();
+        }
+        let elapsed = started.elapsed();
+
+        assert_eq!(row_counts(&db, from_slot, to_slot).await[3], SLOTS);
+        // The cluster produces a block every 400ms; keep at least twice that pace,
+        // debug build included
+        assert!(elapsed < Duration::from_millis(200) * SLOTS as u32, "{:?} for {} slots ({} rows)", elapsed, SLOTS, rows);
+
+        delete_slots(&db, from_slot, to_slot).await;
+    }
 }
@@ -0,0 +1,246 @@
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::transaction::TransactionError;
+use solana_transaction_status::{option_serializer::OptionSerializer, UiTransactionStatusMeta};
+
+/// Why a transaction failed, from its error and program logs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureReason {
+    /// Swap output below the caller's minimum
+    Slippage,
+    /// Oracle price too old for the program to use
+    StaleOracle,
+    ComputeBudgetExceeded,
+    InsufficientFunds,
+    /// Program-specific error code no log pattern explains
+    CustomError,
+    /// Any other instruction error
+    InstructionError,
+    /// Rejected outside instruction execution (fees, account locks, blockhash...)
+    TransactionError,
+}
+
+/// Classified failure of one transaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionFailure {
+    pub reason: FailureReason,
+    /// Top-level instruction that failed
+    pub instruction_index: Option<u8>,
+    /// `InstructionError::Custom` code
+    pub error_code: Option<u32>,
+}
+
+/// Lowercase log fragments for failures that programs report as custom error codes,
+/// which mean something different in every program
+const LOG_PATTERNS: &[(&str, FailureReason)] = &[
+    ("slippage", FailureReason::Slippage),
+    ("amountoutbelowminimum", FailureReason::Slippage),
+    ("toolittleoutputreceived", FailureReason::Slippage),
+    ("stale oracle", FailureReason::StaleOracle),
+    ("staleoracle", FailureReason::StaleOracle),
+    ("oracle is stale", FailureReason::StaleOracle),
+    ("oraclestale", FailureReason::StaleOracle),
+    ("stale price", FailureReason::StaleOracle),
+    ("pricetoostale", FailureReason::StaleOracle),
+    ("exceeded cus meter", FailureReason::ComputeBudgetExceeded),
+    ("insufficient funds", FailureReason::InsufficientFunds),
+    ("insufficient lamports", FailureReason::InsufficientFunds),
+];
+
+/// Classify a transaction's failure, or `None` if it succeeded
+pub fn classify(meta: &UiTransactionStatusMeta) -> Option<TransactionFailure> {
+    let err = meta.err.as_ref()?;
+
+    let (instruction_index, instruction_error) = match err {
+        TransactionError::InstructionError(index, error) => (Some(*index), Some(error)),
+        _ => (None, None),
+    };
+    let error_code = match instruction_error {
+        Some(InstructionError::Custom(code)) => Some(*code),
+        _ => None,
+    };
+
+    let reason = match (err, instruction_error) {
+        (_, Some(InstructionError::ComputationalBudgetExceeded)) => FailureReason::ComputeBudgetExceeded,
+        (_, Some(InstructionError::InsufficientFunds))
+        | (TransactionError::InsufficientFundsForFee | TransactionError::InsufficientFundsForRent { .. }, _) => {
+            FailureReason::InsufficientFunds
+        }
+        _ => match (instruction_index.and_then(|index| reason_from_logs(meta, index)), instruction_error) {
+            (Some(reason), _) => reason,
+            (None, Some(InstructionError::Custom(_))) => FailureReason::CustomError,
+            (None, Some(_)) => FailureReason::InstructionError,
+            (None, None) => FailureReason::TransactionError,
+        },
+    };
+
+    Some(TransactionFailure { reason, instruction_index, error_code })
+}
+
+/// First known pattern counting back from the last log line of the failing instruction,
+/// where the error is reported
+fn reason_from_logs(meta: &UiTransactionStatusMeta, instruction_index: u8) -> Option<FailureReason> {
+    let OptionSerializer::Some(logs) = &meta.log_messages else {
+        return None;
+    };
+
+    failing_instruction_logs(logs, instruction_index).iter().rev().find_map(|line| {
+        let line = line.to_lowercase();
+        LOG_PATTERNS.iter()
+            .find(|(pattern, _)| line.contains(pattern))
+            .map(|(_, reason)| *reason)
+    })
+}
+
+/// Log lines of the top-level instruction at `instruction_index`, without the output of
+/// programs it called that returned successfully. Empty if its logs can't be found, as
+/// when they were truncated.
+fn failing_instruction_logs(logs: &[String], instruction_index: u8) -> Vec<&str> {
+    let mut top_level = logs.iter().enumerate()
+        .filter(|(_, line)| is_invoke(line) && line.ends_with(" invoke [1]"))
+        .map(|(position, _)| position)
+        .skip(instruction_index as usize);
+    let Some(start) = top_level.next() else {
+        return Vec::new();
+    };
+    let end = top_level.next().unwrap_or(logs.len());
+
+    // Lines per open invocation, innermost last
+    let mut frames: Vec<Vec<&str>> = vec![Vec::new()];
+    for line in &logs[start..end] {
+        if is_invoke(line) {
+            frames.push(vec![line]);
+        } else if line.starts_with("Program ") && line.ends_with(" success") {
+            frames.pop();
+            if frames.is_empty() {
+                frames.push(Vec::new());
+            }
+        } else if let Some(frame) = frames.last_mut() {
+            frame.push(line);
+        }
+    }
+    frames.concat()
+}
+
+fn is_invoke(line: &str) -> bool {
+    line.starts_with("Program ") && line.contains(" invoke [")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failed_meta(err: serde_json::Value, logs: &[&str]) -> UiTransactionStatusMeta {
+        serde_json::from_value(serde_json::json!({
+            "err": err,
+            "status": { "Err": err },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "logMessages": logs,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_custom_errors_are_explained_by_logs() {
+        let err = serde_json::json!({ "InstructionError": [2, { "Custom": 6001 }] });
+
+        let meta = failed_meta(err.clone(), &[
+            "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+            "Program ComputeBudget111111111111111111111111111111 success",
+            "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+            "Program ComputeBudget111111111111111111111111111111 success",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+            "Program log: AnchorError occurred. Error Code: SlippageToleranceExceeded. Error Number: 6001. Error Message: Slippage tolerance exceeded.",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 failed: custom program error: 0x1771",
+        ]);
+        assert_eq!(classify(&meta), Some(TransactionFailure {
+            reason: FailureReason::Slippage,
+            instruction_index: Some(2),
+            error_code: Some(6001),
+        }));
+
+        let meta = failed_meta(serde_json::json!({ "InstructionError": [0, { "Custom": 6001 }] }), &[
+            "Program 4MangoMjqJ2firMokCjjGgoK8d4MXcrgL7XJaL3w6fVg invoke [1]",
+            "Program log: Error: Oracle is stale",
+            "Program 4MangoMjqJ2firMokCjjGgoK8d4MXcrgL7XJaL3w6fVg failed: custom program error: 0x1771",
+        ]);
+        assert_eq!(classify(&meta).unwrap().reason, FailureReason::StaleOracle);
+
+        let meta = failed_meta(serde_json::json!({ "InstructionError": [0, { "Custom": 1 }] }), &[]);
+        assert_eq!(classify(&meta).unwrap().reason, FailureReason::CustomError);
+    }
+
+    #[test]
+    fn test_runtime_errors() {
+        let meta = failed_meta(
+            serde_json::json!({ "InstructionError": [1, "ProgramFailedToComplete"] }),
+            &["Program ComputeBudget111111111111111111111111111111 invoke [1]",
+              "Program ComputeBudget111111111111111111111111111111 success",
+              "Program whirLbMiicVdio4qvUfM5KAg6Ct8VwpYC1LDsqNuNmM invoke [1]",
+              "Program whirLbMiicVdio4qvUfM5KAg6Ct8VwpYC1LDsqNuNmM consumed 200000 of 200000 compute units",
+              "Program whirLbMiicVdio4qvUfM5KAg6Ct8VwpYC1LDsqNuNmM failed: exceeded CUs meter at BPF instruction"],
+        );
+        assert_eq!(classify(&meta).unwrap().reason, FailureReason::ComputeBudgetExceeded);
+
+        let meta = failed_meta(serde_json::json!("InsufficientFundsForFee"), &[]);
+        assert_eq!(classify(&meta).unwrap().reason, FailureReason::InsufficientFunds);
+
+        let meta = failed_meta(serde_json::json!("AccountInUse"), &[]);
+        assert_eq!(classify(&meta), Some(TransactionFailure {
+            reason: FailureReason::TransactionError,
+            instruction_index: None,
+            error_code: None,
+        }));
+    }
+
+    #[test]
+    fn test_logs_of_successful_instructions_are_ignored() {
+        let err = serde_json::json!({ "InstructionError": [1, { "Custom": 1 }] });
+
+        // An earlier instruction, and a CPI of the failing one, log about slippage and succeed
+        let meta = failed_meta(err, &[
+            "Program whirLbMiicVdio4qvUfM5KAg6Ct8VwpYC1LDsqNuNmM invoke [1]",
+            "Program log: Slippage check passed",
+            "Program whirLbMiicVdio4qvUfM5KAg6Ct8VwpYC1LDsqNuNmM success",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+            "Program 675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8 invoke [2]",
+            "Program log: slippage within tolerance",
+            "Program 675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8 success",
+            "Program log: Error: route is no longer valid",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 failed: custom program error: 0x1",
+        ]);
+        assert_eq!(classify(&meta).unwrap().reason, FailureReason::CustomError);
+
+        // A failing CPI still explains the failure
+        let meta = failed_meta(serde_json::json!({ "InstructionError": [0, { "Custom": 6001 }] }), &[
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+            "Program whirLbMiicVdio4qvUfM5KAg6Ct8VwpYC1LDsqNuNmM invoke [2]",
+            "Program log: AnchorError occurred. Error Code: AmountOutBelowMinimum. Error Number: 6036.",
+            "Program whirLbMiicVdio4qvUfM5KAg6Ct8VwpYC1LDsqNuNmM failed: custom program error: 0x1794",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 failed: custom program error: 0x1771",
+        ]);
+        assert_eq!(classify(&meta).unwrap().reason, FailureReason::Slippage);
+    }
+
+    #[test]
+    fn test_logs_are_taken_from_the_failing_instruction_only() {
+        // The failing instruction comes before the last top-level invocation in the logs
+        let meta = failed_meta(serde_json::json!({ "InstructionError": [0, { "Custom": 1 }] }), &[
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+            "Program log: Error: route is no longer valid",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 failed: custom program error: 0x1",
+            "Program whirLbMiicVdio4qvUfM5KAg6Ct8VwpYC1LDsqNuNmM invoke [1]",
+            "Program log: slippage exceeded",
+        ]);
+        assert_eq!(classify(&meta).unwrap().reason, FailureReason::CustomError);
+
+        // Truncated before the failing instruction started, so no log explains it
+        let meta = failed_meta(serde_json::json!({ "InstructionError": [1, { "Custom": 1 }] }), &[
+            "Program whirLbMiicVdio4qvUfM5KAg6Ct8VwpYC1LDsqNuNmM invoke [1]",
+            "Program log: slippage exceeded",
+            "Log truncated",
+        ]);
+        assert_eq!(classify(&meta).unwrap().reason, FailureReason::CustomError);
+    }
+}
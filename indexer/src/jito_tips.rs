@@ -0,0 +1,148 @@
+use std::collections::HashSet;
+use once_cell::sync::Lazy;
+use solana_sdk::{message::VersionedMessage, pubkey::Pubkey, system_program};
+use solana_transaction_status::{option_serializer::OptionSerializer, UiInstruction, UiTransactionStatusMeta};
+
+use crate::account_keys::TransactionAccount;
+
+/// Jito tip payment accounts. Bundles pay their tip to any one of them.
+pub const JITO_TIP_ACCOUNTS: [&str; 8] = [
+    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKc5wPdSSdBBnizYZmasA",
+];
+
+static TIP_ACCOUNTS: Lazy<HashSet<Pubkey>> = Lazy::new(|| {
+    JITO_TIP_ACCOUNTS.iter().map(|account| account.parse().unwrap()).collect()
+});
+
+/// `SystemInstruction::Transfer` discriminant (u32 LE), followed by the lamports (u64 LE)
+const SYSTEM_TRANSFER: u32 = 2;
+
+/// Jito tips offered by one transaction. A failed transaction's transfers are
+/// reverted, so its tip is never actually paid.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Tip {
+    pub lamports: u64,
+    /// Tip account receiving the largest transfer
+    pub account: Option<Pubkey>,
+}
+
+/// Sum the System transfers to Jito tip accounts, inner instructions included.
+/// `accounts` must be in runtime order, as returned by `resolve_accounts`.
+pub fn detect_tip(
+    message: &VersionedMessage,
+    meta: Option<&UiTransactionStatusMeta>,
+    accounts: &[TransactionAccount],
+) -> Tip {
+    let key = |index: u8| accounts.get(index as usize).map(|account| account.pubkey);
+    let mut tip = Tip::default();
+    let mut largest = 0;
+
+    let mut record = |program_id_index: u8, ix_accounts: &[u8], data: &[u8]| {
+        let recipient = ix_accounts.get(1).and_then(|&i| key(i));
+        let Some((recipient, lamports)) = transfer(key(program_id_index), recipient, data) else {
+            return;
+        };
+        if TIP_ACCOUNTS.contains(&recipient) {
+            tip.lamports += lamports;
+            if lamports > largest {
+                largest = lamports;
+                tip.account = Some(recipient);
+            }
+        }
+    };
+
+    for ix in message.instructions() {
+        record(ix.program_id_index, &ix.accounts, &ix.data);
+    }
+
+    if let Some(OptionSerializer::Some(inner)) = meta.map(|m| &m.inner_instructions) {
+        for ix in inner.iter().flat_map(|group| &group.instructions) {
+            let UiInstruction::Compiled(ix) = ix else { continue };
+            if let Ok(data) = bs58::decode(&ix.data).into_vec() {
+                record(ix.program_id_index, &ix.accounts, &data);
+            }
+        }
+    }
+
+    tip
+}
+
+/// Recipient and amount of a System transfer
+fn transfer(program: Option<Pubkey>, recipient: Option<Pubkey>, data: &[u8]) -> Option<(Pubkey, u64)> {
+    if program? != system_program::id() || data.len() != 12 {
+        return None;
+    }
+    let (discriminant, lamports) = data.split_at(4);
+    if u32::from_le_bytes(discriminant.try_into().unwrap()) != SYSTEM_TRANSFER {
+        return None;
+    }
+    Some((recipient?, u64::from_le_bytes(lamports.try_into().unwrap())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account_keys::resolve_accounts;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::{message::Message, system_instruction};
+
+    #[test]
+    fn test_tips_are_summed_across_inner_instructions() {
+        let (payer, router) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (tip_account, other_tip_account): (Pubkey, Pubkey) =
+            (JITO_TIP_ACCOUNTS[0].parse().unwrap(), JITO_TIP_ACCOUNTS[5].parse().unwrap());
+
+        // A direct tip, a plain transfer, and a larger tip paid by the router through CPI
+        let message = Message::new(
+            &[
+                system_instruction::transfer(&payer, &tip_account, 10_000),
+                system_instruction::transfer(&payer, &Pubkey::new_unique(), 1_000_000),
+                Instruction::new_with_bytes(router, &[], vec![
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new(other_tip_account, false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ]),
+            ],
+            Some(&payer),
+        );
+        let keys = &message.account_keys;
+        let index = |key: &Pubkey| keys.iter().position(|k| k == key).unwrap() as u8;
+        let cpi = system_instruction::transfer(&payer, &other_tip_account, 25_000);
+        let meta: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "innerInstructions": [{
+                "index": 2,
+                "instructions": [{
+                    "programIdIndex": index(&system_program::id()),
+                    "accounts": [index(&payer), index(&other_tip_account)],
+                    "data": bs58::encode(&cpi.data).into_string(),
+                    "stackHeight": 2,
+                }],
+            }],
+        }))
+        .unwrap();
+
+        let message = VersionedMessage::Legacy(message);
+        let accounts = resolve_accounts(&message, Some(&meta));
+
+        assert_eq!(detect_tip(&message, Some(&meta), &accounts), Tip {
+            lamports: 35_000,
+            account: Some(other_tip_account),
+        });
+        assert_eq!(detect_tip(&message, None, &accounts), Tip {
+            lamports: 10_000,
+            account: Some(tip_account),
+        });
+    }
+}
@@ -55,16 +55,35 @@ pub static KNOWN_PROGRAMS: Lazy<HashMap<&'static str, &'static str>> = Lazy::new
     m
 });
 
+/// AMM programs: the accounts they own are pools and their vaults and tick arrays
+pub const AMM_PROGRAMS: &[&str] = &[
+    "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8",
+    "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK",
+    "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYC1LDsqNuNmM",
+    "9W959DqEETiGZocYWCQPaJ6sBmUzgfxXfqGeTEdp3aQP",
+    "LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo",
+    "Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UG",
+];
+
+/// Central limit orderbook programs
+pub const ORDERBOOK_PROGRAMS: &[&str] = &[
+    "PhoeNiXZ8ByJGLkxNfZRnkUfjvmuYqLR89jjFHGqdXY",
+    "srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX",
+    "opnb2LAfJYbRMAHHvqjCwQxanZn7ReEHp1k81EohpZb",
+];
+
+/// Price oracle programs, Pyth's pull oracle receiver included
+pub const ORACLE_PROGRAMS: &[&str] = &[
+    "FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH",
+    "rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ",
+    "SW1TCH7qEPTdLsDHRgPuMQjbQxKdH2aBStViMFnt64f",
+];
+
 /// Get label for a program ID
 pub fn get_program_label(program_id: &str) -> Option<&'static str> {
     KNOWN_PROGRAMS.get(program_id).copied()
 }
 
-/// Check if account is a known program
-pub fn is_known_program(pubkey: &str) -> bool {
-    KNOWN_PROGRAMS.contains_key(pubkey)
-}
-
 /// Known high-contention accounts (popular pools, vaults, etc.)
 pub static KNOWN_ACCOUNTS: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
     let mut m = HashMap::new();
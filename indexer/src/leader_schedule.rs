@@ -0,0 +1,189 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::epoch_schedule::EpochSchedule;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::warn;
+
+/// Slots fetched per `getSlotLeaders` call (the RPC allows up to 5000)
+const LEADER_CHUNK_SLOTS: u64 = 1000;
+
+/// How long a failed chunk is left alone before it's requested again
+const RETRY_AFTER: Duration = Duration::from_secs(10);
+
+/// Leaders of one epoch by slot index
+type EpochLeaders = Vec<Option<Arc<str>>>;
+
+/// How slot leaders are looked up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderLookup {
+    /// No lookups, leaders are left NULL (e.g. replaying recorded blocks offline)
+    Off,
+    /// `getSlotLeaders` in chunks around the slots being processed, for the live follower
+    SlotLeaders,
+    /// `getLeaderSchedule` once per epoch, for backfills. Nodes only keep the schedule
+    /// of recent epochs; slots of older ones resolve to `None` after a single call.
+    EpochSchedule,
+}
+
+/// Leader identity per slot, fetched and cached according to a `LeaderLookup`
+pub struct LeaderSchedule {
+    client: Arc<RpcClient>,
+    lookup: LeaderLookup,
+    /// First slot of the cached chunk and its leaders
+    chunk: Option<(u64, Vec<String>)>,
+    /// Chunk, epoch or epoch schedule whose fetch failed, and when
+    failed: Option<(u64, Instant)>,
+    epoch_schedule: Option<EpochSchedule>,
+    /// Cached epoch and its leaders by slot index, `None` if the node doesn't have it
+    epoch: Option<(u64, Option<EpochLeaders>)>,
+}
+
+impl LeaderSchedule {
+    pub fn new(client: Arc<RpcClient>, lookup: LeaderLookup) -> Self {
+        Self { client, lookup, chunk: None, failed: None, epoch_schedule: None, epoch: None }
+    }
+
+    /// Leader of `slot`, or `None` if the schedule isn't available
+    pub async fn leader(&mut self, slot: u64) -> Option<String> {
+        match self.lookup {
+            LeaderLookup::Off => None,
+            LeaderLookup::SlotLeaders => self.chunk_leader(slot).await,
+            LeaderLookup::EpochSchedule => self.epoch_leader(slot).await,
+        }
+    }
+
+    fn retry_pending(&self, key: u64) -> bool {
+        self.failed.is_some_and(|(failed, at)| failed == key && at.elapsed() < RETRY_AFTER)
+    }
+
+    async fn chunk_leader(&mut self, slot: u64) -> Option<String> {
+        let chunk_start = slot - slot % LEADER_CHUNK_SLOTS;
+
+        if self.chunk.as_ref().is_none_or(|(start, _)| *start != chunk_start) {
+            if self.retry_pending(chunk_start) {
+                return None;
+            }
+
+            match self.client.get_slot_leaders(chunk_start, LEADER_CHUNK_SLOTS).await {
+                Ok(leaders) => {
+                    let leaders = leaders.iter().map(|leader| leader.to_string()).collect();
+                    self.chunk = Some((chunk_start, leaders));
+                    self.failed = None;
+                }
+                Err(e) => {
+                    warn!("⚠️ Failed to fetch slot leaders from {}: {}", chunk_start, e);
+                    self.failed = Some((chunk_start, Instant::now()));
+                    return None;
+                }
+            }
+        }
+
+        let (start, leaders) = self.chunk.as_ref()?;
+        leaders.get((slot - start) as usize).cloned()
+    }
+
+    async fn epoch_leader(&mut self, slot: u64) -> Option<String> {
+        if self.epoch_schedule.is_none() {
+            // Keyed on slot 0, the epoch schedule doesn't depend on the slot
+            if self.retry_pending(0) {
+                return None;
+            }
+            match self.client.get_epoch_schedule().await {
+                Ok(schedule) => self.epoch_schedule = Some(schedule),
+                Err(e) => {
+                    warn!("⚠️ Failed to fetch the epoch schedule: {}", e);
+                    self.failed = Some((0, Instant::now()));
+                    return None;
+                }
+            }
+        }
+        let schedule = self.epoch_schedule.as_ref()?;
+        let (epoch, slot_index) = schedule.get_epoch_and_slot_index(slot);
+
+        if self.epoch.as_ref().is_none_or(|(cached, _)| *cached != epoch) {
+            if self.retry_pending(epoch) {
+                return None;
+            }
+
+            let slots_in_epoch = schedule.get_slots_in_epoch(epoch) as usize;
+            let leaders = match self.client.get_leader_schedule(Some(slot)).await {
+                Ok(Some(by_leader)) => {
+                    let mut leaders = vec![None; slots_in_epoch];
+                    for (leader, indexes) in by_leader {
+                        let leader: Arc<str> = leader.into();
+                        for index in indexes.into_iter().filter(|&i| i < slots_in_epoch) {
+                            leaders[index] = Some(leader.clone());
+                        }
+                    }
+                    self.failed = None;
+                    Some(leaders)
+                }
+                // Not kept by the node, asking again won't help
+                Ok(None) => {
+                    warn!("⚠️ No leader schedule for epoch {}, its leaders are left empty", epoch);
+                    None
+                }
+                Err(e) => {
+                    warn!("⚠️ Failed to fetch the leader schedule of epoch {}: {}", epoch, e);
+                    self.failed = Some((epoch, Instant::now()));
+                    return None;
+                }
+            };
+            self.epoch = Some((epoch, leaders));
+        }
+
+        let (_, leaders) = self.epoch.as_ref()?;
+        leaders.as_ref()?.get(slot_index as usize)?.as_deref().map(str::to_string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lock_test_support::{Fault, MockChain, MockRpcServer, DEFAULT_LEADER};
+    use solana_sdk::commitment_config::CommitmentConfig;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[tokio::test]
+    async fn test_leaders_are_fetched_once_per_chunk() {
+        let leader = Pubkey::new_unique().to_string();
+        let server = MockRpcServer::start(MockChain::new().with_leader(2001, &leader)).await;
+        let client = Arc::new(RpcClient::new_with_commitment(server.url(), CommitmentConfig::confirmed()));
+        let mut schedule = LeaderSchedule::new(client, LeaderLookup::SlotLeaders);
+
+        assert_eq!(schedule.leader(2000).await.as_deref(), Some(DEFAULT_LEADER));
+        assert_eq!(schedule.leader(2001).await.as_deref(), Some(leader.as_str()));
+        assert_eq!(schedule.leader(2999).await.as_deref(), Some(DEFAULT_LEADER));
+        assert_eq!(server.calls("getSlotLeaders"), 1);
+
+        assert!(schedule.leader(3000).await.is_some());
+        assert_eq!(server.calls("getSlotLeaders"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_epoch_schedule_retries_failures_but_not_missing_epochs() {
+        let leader = Pubkey::new_unique().to_string();
+        let chain = MockChain::new().with_slots_per_epoch(100).with_leader(212, &leader).with_tip(250);
+        let server = MockRpcServer::start(chain).await;
+        let client = Arc::new(RpcClient::new_with_commitment(server.url(), CommitmentConfig::confirmed()));
+        let mut schedule = LeaderSchedule::new(client, LeaderLookup::EpochSchedule);
+
+        server.inject("getLeaderSchedule", Fault::ServerError, 1);
+        assert_eq!(schedule.leader(210).await, None);
+        assert_eq!(schedule.leader(211).await, None);
+        assert_eq!(server.calls("getLeaderSchedule"), 1);
+
+        // Once the retry delay is over, the epoch is fetched again
+        schedule.failed = schedule.failed.map(|(epoch, at)| (epoch, at - RETRY_AFTER));
+        assert_eq!(schedule.leader(212).await.as_deref(), Some(leader.as_str()));
+        assert_eq!(schedule.leader(213).await.as_deref(), Some(DEFAULT_LEADER));
+        assert_eq!(server.calls("getLeaderSchedule"), 2);
+
+        // The node no longer has epoch 0, which is asked for only once
+        assert_eq!(schedule.leader(50).await, None);
+        assert_eq!(schedule.leader(51).await, None);
+        assert_eq!(server.calls("getLeaderSchedule"), 3);
+        assert_eq!(server.calls("getEpochSchedule"), 1);
+    }
+}
@@ -2,18 +2,27 @@ use std::collections::HashMap;
 use solana_sdk::pubkey::Pubkey;
 
 pub struct LockDetector {
+    /// Writers of each account, in block order
     write_attempts: HashMap<String, Vec<String>>,
+    /// Transactions with at least one write lock, in block order
+    writers: Vec<String>,
+    read_attempts: HashMap<String, Vec<String>>,
 }
 
 impl LockDetector {
     pub fn new() -> Self {
         Self {
             write_attempts: HashMap::new(),
+            writers: Vec::new(),
+            read_attempts: HashMap::new(),
         }
     }
 
     /// Track a transaction's writable accounts
     pub fn track_transaction(&mut self, signature: &str, writable_accounts: &[Pubkey]) {
+        if !writable_accounts.is_empty() {
+            self.writers.push(signature.to_string());
+        }
         for account in writable_accounts {
             let account_key = account.to_string();
             self.write_attempts
@@ -23,6 +32,43 @@ impl LockDetector {
         }
     }
 
+    /// Tracked transactions in block order
+    pub fn transactions(&self) -> &[String] {
+        &self.writers
+    }
+
+    /// Each write-locked account with its writers in block order
+    pub fn write_queues(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.write_attempts.iter().map(|(account, txs)| (account.as_str(), txs.as_slice()))
+    }
+
+    /// Track a transaction's readonly accounts
+    pub fn track_reads(&mut self, signature: &str, readonly_accounts: &[Pubkey]) {
+        for account in readonly_accounts {
+            self.read_attempts
+                .entry(account.to_string())
+                .or_default()
+                .push(signature.to_string());
+        }
+    }
+
+    /// Whether any tracked transaction write-locks the account
+    pub fn has_writers(&self, account: &str) -> bool {
+        self.write_attempts.contains_key(account)
+    }
+
+    /// Readers of an account that is also write-locked: the scheduler can't run
+    /// any of them alongside a writer, so each one is a read-write conflict
+    pub fn read_conflicts(&self, account: &str) -> u32 {
+        if !self.has_writers(account) {
+            return 0;
+        }
+        self.read_attempts
+            .get(account)
+            .map(|txs| txs.len() as u32)
+            .unwrap_or(0)
+    }
+
     pub fn calculate_contention(&self, account: &str) -> f32 {
         self.write_attempts
             .get(account)
@@ -43,6 +89,8 @@ impl LockDetector {
     #[allow(dead_code)]
     pub fn reset(&mut self) {
         self.write_attempts.clear();
+        self.writers.clear();
+        self.read_attempts.clear();
     }
 
     /// Check if an account is "hot" (high contention)
@@ -80,4 +128,20 @@ mod tests {
         assert_eq!(detector.calculate_contention(&account.to_string()), 1.0);
         assert!(!detector.is_hot_account(&account.to_string(), 2.0));
     }
+
+    #[test]
+    fn test_read_conflicts_need_a_writer() {
+        let mut detector = LockDetector::new();
+        let pool = Pubkey::new_unique();
+        let oracle = Pubkey::new_unique();
+
+        detector.track_transaction("tx1", &[pool]);
+        detector.track_reads("tx2", &[pool, oracle]);
+        detector.track_reads("tx3", &[pool, oracle]);
+
+        assert_eq!(detector.read_conflicts(&pool.to_string()), 2);
+        // Shared reads don't block each other
+        assert_eq!(detector.read_conflicts(&oracle.to_string()), 0);
+        assert_eq!(detector.calculate_contention(&pool.to_string()), 1.0);
+    }
 }
@@ -1,17 +1,36 @@
 use anyhow::Result;
+use clap::Parser;
 use tracing::{info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use std::sync::Arc;
+use std::time::Duration;
 
+mod account_enricher;
+mod account_keys;
+mod block_processor;
+mod block_source;
+mod cli;
+mod compute_budget;
+mod conflict_graph;
 mod config;
 mod database;
+mod failure_reason;
+mod jito_tips;
+mod leader_schedule;
 mod rpc_stream;
 mod lock_detector;
+mod pipeline;
+mod subscription;
 mod live_tracker;
+mod write_attribution;
 mod known_programs;
 
+use account_enricher::{AccountEnricher, ENRICH_INTERVAL};
+use block_source::DirectoryBlockSource;
+use cli::{BackfillArgs, Cli, Command, ReplayArgs};
 use config::Config;
 use database::Database;
+use leader_schedule::LeaderLookup;
 use rpc_stream::RpcStream;
 use live_tracker::LiveTracker;
 
@@ -26,6 +45,8 @@ async fn main() -> Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    let cli = Cli::parse();
+
     info!("🚀 Starting Solana Lock Indexer");
 
     // Load configuration
@@ -36,12 +57,18 @@ async fn main() -> Result<()> {
     let database = Database::new(&config.database_url).await?;
     info!("💾 Connected to database");
 
+    match cli.command {
+        Some(Command::Backfill(args)) => return run_backfill(&config, &database, args).await,
+        Some(Command::Replay(args)) => return run_replay(&config, &database, args).await,
+        Some(Command::Run) | None => {}
+    }
+
     // Initialize live tracker (10-slot window)
     let live_tracker = Arc::new(LiveTracker::new(10));
     info!("📊 Live tracker initialized (10-slot window)");
 
     // Initialize RPC stream
-    let mut rpc_stream = RpcStream::new(config.rpc_endpoint.clone());
+    let mut rpc_stream = RpcStream::new(&config);
     info!("🔗 Connected to Solana RPC");
 
     // Spawn cleanup task
@@ -53,16 +80,72 @@ async fn main() -> Result<()> {
         }
     });
 
-    // Main processing loop
+    // Spawn enrichment task for newly hot accounts
+    if config.enrich_accounts {
+        let mut enricher = AccountEnricher::new(rpc_stream.client())
+            .with_refresh_after(Duration::from_secs(config.enrich_refresh_hours * 60 * 60));
+        let (tracker, sink) = (live_tracker.clone(), database.clone());
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(ENRICH_INTERVAL).await;
+                match enricher.enrich_hot_accounts(&tracker, &sink).await {
+                    Ok(0) => {}
+                    Ok(n) => info!("🏷️ Enriched {} hot accounts", n),
+                    Err(e) => warn!("Account enrichment failed: {:#}", e),
+                }
+            }
+        });
+    }
+
+    // Main processing loop, restarts the pipeline after the last processed slot on failure
     info!("📊 Starting real-time transaction monitoring...");
     loop {
-        match rpc_stream.process_slot_live(&database, &live_tracker).await {
-            Ok(_) => {}
-            Err(e) => {
-                warn!("Error processing slot: {}", e);
-                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-            }
+        if let Err(e) = rpc_stream.run(&database, &live_tracker).await {
+            warn!("Block pipeline error: {:#}", e);
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        }
+    }
+}
+
+/// Index a historical range given either as slots or as a time window
+async fn run_backfill(config: &Config, database: &Database, args: BackfillArgs) -> Result<()> {
+    // Older slots are outside what getSlotLeaders serves, look leaders up per epoch
+    let leader_lookup = if config.attribute_leaders { LeaderLookup::EpochSchedule } else { LeaderLookup::Off };
+    let mut rpc_stream = RpcStream::new(config).with_leader_lookup(leader_lookup);
+
+    let (from_slot, to_slot) = match (args.from_slot, args.to_slot, args.from_time, args.to_time) {
+        (Some(from), Some(to), _, _) => (from, to),
+        (_, _, Some(from), Some(to)) => {
+            let from_slot = rpc_stream.slot_at_time(from).await?;
+            let to_slot = rpc_stream.slot_at_time(to).await?;
+            info!("🕒 {} .. {} resolves to slots {}..={}", from, to, from_slot, to_slot);
+            (from_slot, to_slot)
         }
+        _ => anyhow::bail!("backfill needs --from-slot/--to-slot or --from-time/--to-time"),
+    };
+
+    if from_slot > to_slot {
+        anyhow::bail!("Empty backfill range: {} > {}", from_slot, to_slot);
     }
+
+    rpc_stream.backfill(from_slot, to_slot, args.concurrency, database).await
 }
 
+/// Feed recorded blocks through detection, the live tracker and the database
+async fn run_replay(config: &Config, database: &Database, args: ReplayArgs) -> Result<()> {
+    let mut source = DirectoryBlockSource::open(&args.dir)?;
+    if !args.max_speed {
+        source = source.with_speed(args.speed);
+    }
+
+    let live_tracker = Arc::new(LiveTracker::new(10));
+    // Recorded blocks replay offline, without RPC lookups
+    let mut rpc_stream = RpcStream::new(config).with_leader_lookup(LeaderLookup::Off);
+
+    info!("⏯️ Replaying blocks from {}", args.dir.display());
+    // No checkpoint, replayed slots must not move the live follower
+    rpc_stream.consume(&mut source, database, Some(&live_tracker), None).await?;
+    info!("✅ Replay complete");
+
+    Ok(())
+}
@@ -0,0 +1,260 @@
+use anyhow::{Context, Result};
+use futures::StreamExt;
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcBlockConfig;
+use solana_client::rpc_custom_error::{
+    JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_SLOT_SKIPPED, JSON_RPC_SERVER_ERROR_SLOT_SKIPPED,
+};
+use solana_client::rpc_request::RpcError;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_transaction_status::{TransactionDetails, UiConfirmedBlock, UiTransactionEncoding};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::warn;
+
+/// Attempts per block before the fetch stage gives up on a slot
+const MAX_FETCH_ATTEMPTS: u32 = 5;
+
+/// Delay before the first retry, doubled on each attempt
+const FETCH_RETRY_BASE_MS: u64 = 200;
+
+/// Maximum slot range a single `getBlocks` call accepts
+pub const MAX_GET_BLOCKS_RANGE: u64 = 500_000;
+
+/// A slot handed from discovery to the fetch stage
+pub enum SlotJob {
+    /// The slot has a confirmed block to fetch
+    Fetch(u64),
+    /// The slot is not on the confirmed chain
+    Skipped(u64),
+    /// The block was already delivered by `blockSubscribe`
+    Prefetched(u64, Box<UiConfirmedBlock>),
+}
+
+impl SlotJob {
+    pub fn slot(&self) -> u64 {
+        match self {
+            SlotJob::Fetch(slot) | SlotJob::Skipped(slot) | SlotJob::Prefetched(slot, _) => *slot,
+        }
+    }
+}
+
+/// Output of the fetch stage, in slot order
+pub struct FetchedSlot {
+    pub slot: u64,
+    /// `Ok(None)` when the slot was skipped
+    pub block: Result<Option<UiConfirmedBlock>>,
+}
+
+/// Stage 1: poll `getSlot` and emit every slot after `start_after` up to the tip.
+/// `getBlocks` tells which of them produced a confirmed block; the rest are emitted
+/// as skipped so the fetch stage doesn't wait on blocks that will never exist.
+/// Sending waits while the channel is full, which throttles discovery to the
+/// speed of the stages behind it. Exits when the receiver is dropped.
+pub fn spawn_slot_discovery(
+    client: Arc<RpcClient>,
+    start_after: u64,
+    poll_interval: Duration,
+    jobs: mpsc::Sender<SlotJob>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut next_slot = start_after + 1;
+
+        loop {
+            match discover_slots(&client, next_slot).await {
+                Ok(new_jobs) => {
+                    for job in new_jobs {
+                        next_slot = job.slot() + 1;
+                        if jobs.send(job).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+                Err(e) => warn!("⚠️ Slot discovery failed: {:#}", e),
+            }
+
+            tokio::time::sleep(poll_interval).await;
+        }
+    })
+}
+
+/// Jobs for every slot from `from_slot` up to the confirmed tip
+pub async fn discover_slots(client: &RpcClient, from_slot: u64) -> Result<Vec<SlotJob>> {
+    let current_slot = client.get_slot().await.context("Failed to fetch current slot")?;
+    discover_slots_through(client, from_slot, current_slot).await
+}
+
+/// Jobs for the slots from `from_slot` up to the last confirmed block at or before
+/// `up_to`. Slots after that block are left for a later call, as they may still be
+/// confirmed. At most `MAX_GET_BLOCKS_RANGE` slots are covered per call.
+pub async fn discover_slots_through(client: &RpcClient, from_slot: u64, up_to: u64) -> Result<Vec<SlotJob>> {
+    if up_to < from_slot {
+        return Ok(Vec::new());
+    }
+
+    let end = up_to.min(from_slot + MAX_GET_BLOCKS_RANGE - 1);
+    let produced = client.get_blocks(from_slot, Some(end)).await
+        .context("Failed to fetch produced blocks")?;
+    // A range cut short ends before the confirmed `up_to`, so all of its slots are settled
+    let last = if end < up_to { Some(end) } else { produced.last().copied() };
+    match last {
+        Some(last) => Ok(slot_jobs(from_slot, last, &produced)),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Slots in `[from_slot, to_slot]` that produced a confirmed block, fetched in
+/// ranges `getBlocks` accepts
+pub async fn get_produced_slots(client: &RpcClient, from_slot: u64, to_slot: u64) -> Result<Vec<u64>> {
+    let mut slots = Vec::new();
+    let mut start = from_slot;
+
+    while start <= to_slot {
+        let end = to_slot.min(start + MAX_GET_BLOCKS_RANGE - 1);
+        slots.extend(
+            client.get_blocks(start, Some(end)).await
+                .context("Failed to fetch produced blocks")?
+        );
+        start = end + 1;
+    }
+
+    Ok(slots)
+}
+
+/// Classify `[from_slot, to_slot]` against the sorted list of produced slots
+pub fn slot_jobs(from_slot: u64, to_slot: u64, produced: &[u64]) -> Vec<SlotJob> {
+    (from_slot..=to_slot)
+        .map(|slot| {
+            if produced.binary_search(&slot).is_ok() {
+                SlotJob::Fetch(slot)
+            } else {
+                SlotJob::Skipped(slot)
+            }
+        })
+        .collect()
+}
+
+/// Stage 2: fetch up to `concurrency` blocks at once and forward them in slot order.
+/// Exits when either channel closes.
+pub fn spawn_block_fetcher(
+    client: Arc<RpcClient>,
+    jobs: mpsc::Receiver<SlotJob>,
+    concurrency: usize,
+    blocks: mpsc::Sender<FetchedSlot>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut fetched = ReceiverStream::new(jobs)
+            .map(|job| {
+                let client = client.clone();
+                async move {
+                    match job {
+                        SlotJob::Fetch(slot) => FetchedSlot {
+                            slot,
+                            block: fetch_block_with_retry(&client, slot).await,
+                        },
+                        SlotJob::Skipped(slot) => FetchedSlot { slot, block: Ok(None) },
+                        SlotJob::Prefetched(slot, block) => FetchedSlot { slot, block: Ok(Some(*block)) },
+                    }
+                }
+            })
+            .buffered(concurrency.max(1));
+
+        while let Some(fetched_slot) = fetched.next().await {
+            if blocks.send(fetched_slot).await.is_err() {
+                return;
+            }
+        }
+    })
+}
+
+/// Fetch a block, retrying transient failures (rate limits, blocks not yet available)
+/// with exponential backoff
+pub async fn fetch_block_with_retry(client: &RpcClient, slot: u64) -> Result<Option<UiConfirmedBlock>> {
+    let mut attempt = 1;
+    loop {
+        match fetch_block(client, slot).await {
+            Ok(block) => return Ok(block),
+            Err(e) if attempt < MAX_FETCH_ATTEMPTS => {
+                let delay = FETCH_RETRY_BASE_MS << (attempt - 1);
+                warn!("⚠️ Fetching slot {} failed (attempt {}), retrying in {}ms: {}", slot, attempt, delay, e);
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+                attempt += 1;
+            }
+            Err(e) => {
+                return Err(anyhow::Error::from(e))
+                    .with_context(|| format!("Failed to fetch block for slot {}", slot));
+            }
+        }
+    }
+}
+
+/// Fetch a confirmed block with full transaction details. Returns `None` for skipped slots.
+async fn fetch_block(client: &RpcClient, slot: u64) -> Result<Option<UiConfirmedBlock>, ClientError> {
+    let config = RpcBlockConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        transaction_details: Some(TransactionDetails::Full),
+        rewards: Some(false),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+
+    match client.get_block_with_config(slot, config).await {
+        Ok(block) => Ok(Some(block)),
+        Err(e) if is_skipped_slot_error(&e) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Whether a `getBlock` error means the slot was skipped (no block will ever exist)
+fn is_skipped_slot_error(err: &ClientError) -> bool {
+    matches!(
+        err.kind(),
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+            if *code == JSON_RPC_SERVER_ERROR_SLOT_SKIPPED
+                || *code == JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_SLOT_SKIPPED
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lock_test_support::{MockChain, MockRpcServer};
+    use serde_json::json;
+
+    #[test]
+    fn test_slot_jobs_mark_missing_slots_skipped() {
+        let jobs: Vec<_> = slot_jobs(100, 104, &[100, 101, 103])
+            .iter()
+            .map(|job| (job.slot(), matches!(job, SlotJob::Skipped(_))))
+            .collect();
+
+        assert_eq!(jobs, vec![(100, false), (101, false), (102, true), (103, false), (104, true)]);
+    }
+
+    #[tokio::test]
+    async fn test_discovery_splits_gaps_larger_than_get_blocks_accepts() {
+        let chain = MockChain::new()
+            .with_block(10, json!({}))
+            .with_block(550_000, json!({}))
+            .with_tip(600_000);
+        let server = MockRpcServer::start(chain).await;
+        let client = RpcClient::new_with_commitment(server.url(), CommitmentConfig::confirmed());
+
+        let first = discover_slots(&client, 1).await.unwrap();
+        assert_eq!((first[0].slot(), first.last().unwrap().slot()), (1, MAX_GET_BLOCKS_RANGE));
+        assert!(matches!(first[9], SlotJob::Fetch(10)));
+        assert_eq!(first.iter().filter(|job| matches!(job, SlotJob::Fetch(_))).count(), 1);
+
+        // The next poll carries on from there, up to the last block
+        let rest = discover_slots(&client, MAX_GET_BLOCKS_RANGE + 1).await.unwrap();
+        assert_eq!(rest[0].slot(), MAX_GET_BLOCKS_RANGE + 1);
+        assert!(matches!(rest.last(), Some(SlotJob::Fetch(550_000))));
+
+        assert_eq!(get_produced_slots(&client, 1, 600_000).await.unwrap(), vec![10, 550_000]);
+        assert_eq!(server.calls("getBlocks"), 4);
+    }
+}
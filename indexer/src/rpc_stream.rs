@@ -1,340 +1,582 @@
 use anyhow::{Context, Result};
-use chrono::Utc;
-use solana_client::rpc_client::RpcClient;
-use solana_sdk::{
-    commitment_config::CommitmentConfig,
-};
-use solana_transaction_status::{UiTransactionEncoding, EncodedTransactionWithStatusMeta};
+use chrono::{DateTime, Utc};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_transaction_status::UiConfirmedBlock;
+use futures::StreamExt;
 use tracing::{info, warn};
+use std::collections::HashSet;
+use std::path::PathBuf;
 use std::sync::Arc;
-use std::collections::HashMap;
+use std::time::Duration;
 
-use crate::database::{Database, WriteLockEvent};
-use crate::lock_detector::LockDetector;
+use crate::block_processor::{BlockProcessor, SLOT_DURATION_MS};
+use crate::block_source::{BlockSource, RecordingSource, RpcBlockSource, RpcSourceOptions, SourceBlock};
+use crate::config::{Config, ResumePolicy};
+use crate::database::{EventSink, SlotBatch};
+use crate::leader_schedule::{LeaderLookup, LeaderSchedule};
 use crate::live_tracker::LiveTracker;
+use crate::pipeline::{fetch_block_with_retry, get_produced_slots};
+
+/// Checkpoint name for the live tip follower
+const CHECKPOINT_NAME: &str = "live";
 
 pub struct RpcStream {
-    rpc_endpoint: String,
+    client: Arc<RpcClient>,
+    source_options: RpcSourceOptions,
+    resume_policy: ResumePolicy,
+    max_backfill_slots: u64,
+    /// Where the live follower records blocks, if anywhere
+    record_dir: Option<PathBuf>,
+    /// Number of buffered rows that triggers a flush
+    batch_size: usize,
     last_processed_slot: u64,
+    processor: BlockProcessor,
+    leaders: LeaderSchedule,
+    /// Rows of whole slots processed but not yet committed
+    pending: SlotBatch,
+    /// Highest slot in the pending batch (events or not)
+    pending_through_slot: Option<u64>,
 }
 
 impl RpcStream {
-    pub fn new(rpc_endpoint: String) -> Self {
+    pub fn new(config: &Config) -> Self {
+        let client = RpcClient::new_with_timeout_and_commitment(
+            config.rpc_endpoint.clone(),
+            Duration::from_secs(config.rpc_timeout_secs),
+            CommitmentConfig::confirmed(),
+        );
+
+        let client = Arc::new(client);
+        let leader_lookup = if config.attribute_leaders { LeaderLookup::SlotLeaders } else { LeaderLookup::Off };
+
         Self {
-            rpc_endpoint,
+            leaders: LeaderSchedule::new(client.clone(), leader_lookup),
+            client,
+            source_options: RpcSourceOptions::from_config(config),
+            resume_policy: config.resume_policy,
+            max_backfill_slots: config.max_backfill_slots,
+            record_dir: config.record_dir.clone(),
+            batch_size: config.batch_size.max(1),
             last_processed_slot: 0,
+            processor: BlockProcessor::new()
+                .with_vote_transactions(config.include_vote_transactions)
+                .with_max_block_compute_units(config.max_block_compute_units),
+            pending: SlotBatch::default(),
+            pending_through_slot: None,
         }
     }
 
-    #[allow(dead_code)]
-    fn create_client(&self) -> RpcClient {
-        RpcClient::new_with_commitment(
-            self.rpc_endpoint.clone(),
-            CommitmentConfig::confirmed(),
-        )
+    /// Change how slot leaders are looked up, e.g. per epoch for a backfill
+    pub fn with_leader_lookup(mut self, lookup: LeaderLookup) -> Self {
+        self.leaders = LeaderSchedule::new(self.client.clone(), lookup);
+        self
+    }
+
+    /// RPC client shared with other tasks
+    pub fn client(&self) -> Arc<RpcClient> {
+        self.client.clone()
     }
 
-    /// Process slot with live tracking for real-time fee estimation
-    pub async fn process_slot_live(
-        &mut self, 
-        database: &Database,
+    /// Pick the first slot to process on startup from the stored checkpoint
+    async fn resume_slot(&self, sink: &dyn EventSink, current_slot: u64) -> Result<u64> {
+        let checkpoint = match sink.get_checkpoint(CHECKPOINT_NAME).await? {
+            Some(slot) => slot,
+            None => {
+                let start = current_slot.saturating_sub(5);
+                info!("🎯 No checkpoint found, starting from slot {}", start);
+                return Ok(start);
+            }
+        };
+
+        let gap = current_slot.saturating_sub(checkpoint);
+        let resume_from = match self.resume_policy {
+            ResumePolicy::Backfill => checkpoint,
+            ResumePolicy::Tip => current_slot.max(checkpoint),
+            ResumePolicy::Bounded => checkpoint.max(current_slot.saturating_sub(self.max_backfill_slots)),
+        };
+
+        if resume_from > checkpoint {
+            warn!(
+                "⏭️ Skipping {} slots after checkpoint {} ({:?} resume policy)",
+                resume_from - checkpoint, checkpoint, self.resume_policy
+            );
+        }
+        info!("🎯 Resuming after slot {} ({} slots behind tip)", resume_from, gap);
+
+        Ok(resume_from)
+    }
+
+    /// Follow the chain tip: an `RpcBlockSource` discovers and fetches blocks
+    /// concurrently (polling or pubsub, per `ingestion_mode`) and this task processes
+    /// and persists them in order. Stages are connected by bounded channels, so a slow
+    /// stage applies backpressure upstream. Blocks are also saved to `record_dir` when set.
+    ///
+    /// Returns when a slot can't be fetched or committed; calling it again restarts
+    /// the pipeline after the last processed slot.
+    pub async fn run(
+        &mut self,
+        sink: &dyn EventSink,
         live_tracker: &Arc<LiveTracker>,
     ) -> Result<()> {
-        let endpoint = self.rpc_endpoint.clone();
-        
-        // Get current slot
-        let current_slot = tokio::task::spawn_blocking(move || {
-            let client = RpcClient::new_with_commitment(endpoint, CommitmentConfig::confirmed());
-            client.get_slot()
-        }).await??;
-
         if self.last_processed_slot == 0 {
-            self.last_processed_slot = current_slot.saturating_sub(5);
-            info!("🎯 Starting from slot {}", self.last_processed_slot);
+            let current_slot = self.client.get_slot().await.context("Failed to fetch current slot")?;
+            self.last_processed_slot = self.resume_slot(sink, current_slot).await?;
         }
 
-        // Process slots we haven't seen yet
-        if current_slot > self.last_processed_slot {
-            for slot in (self.last_processed_slot + 1)..=current_slot {
-                match self.process_single_slot_live(slot, database, live_tracker).await {
-                    Ok(num_events) => {
-                        if num_events > 0 {
-                            info!("✅ Slot {} processed: {} events (live)", slot, num_events);
-                        }
-                    }
-                    Err(e) => {
-                        let err_str = e.to_string();
-                        if !err_str.contains("was skipped") && !err_str.contains("SlotSkipped") {
-                            warn!("⚠️ Error processing slot {}: {}", slot, e);
-                        }
+        let source = RpcBlockSource::start(self.client.clone(), &self.source_options, self.last_processed_slot);
+        let mut source: Box<dyn BlockSource> = match &self.record_dir {
+            Some(dir) => Box::new(RecordingSource::new(source, dir)?),
+            None => Box::new(source),
+        };
+        self.consume(source.as_mut(), sink, Some(live_tracker), Some(CHECKPOINT_NAME)).await?;
+
+        anyhow::bail!("Block pipeline stopped unexpectedly")
+    }
+
+    /// Process every block of `source` in order and commit the events to `sink`,
+    /// flushing partial batches whenever the source has nothing ready. Returns once
+    /// the source is exhausted.
+    pub async fn consume(
+        &mut self,
+        source: &mut dyn BlockSource,
+        sink: &dyn EventSink,
+        live_tracker: Option<&Arc<LiveTracker>>,
+        checkpoint: Option<&str>,
+    ) -> Result<()> {
+        while let Some(SourceBlock { slot, block }) = source.next_block().await? {
+            let num_events = self.process_block(slot, block, sink, live_tracker, checkpoint).await
+                .with_context(|| format!("Failed to process slot {}", slot))?;
+            if num_events > 0 {
+                info!("✅ Slot {} processed: {} events", slot, num_events);
+            }
+            self.last_processed_slot = slot;
+
+            // Caught up with the source, don't hold back a partial batch
+            if source.is_idle() {
+                self.flush(sink, checkpoint).await?;
+            }
+        }
+
+        self.flush(sink, checkpoint).await
+    }
+
+    /// Index a historical slot range. Only slots `getBlocks` reports as produced are
+    /// fetched, `concurrency` at a time; the rest are recorded as skipped. Slots that
+    /// were already processed are left alone. Writes are upserts, so overlapping with the live follower is harmless.
+    pub async fn backfill(
+        &mut self,
+        from_slot: u64,
+        to_slot: u64,
+        concurrency: usize,
+        sink: &dyn EventSink,
+    ) -> Result<()> {
+        let produced: HashSet<u64> = get_produced_slots(&self.client, from_slot, to_slot).await?.into_iter().collect();
+        let indexed = sink.indexed_slots(from_slot, to_slot).await?;
+        let pending: Vec<u64> = (from_slot..=to_slot)
+            .filter(|slot| !indexed.contains(slot))
+            .collect();
+        let to_fetch = pending.iter().filter(|slot| produced.contains(slot)).count();
+
+        info!(
+            "⏪ Backfilling slots {}..={}: {} produced, {} already indexed, {} to fetch",
+            from_slot, to_slot, produced.len(), indexed.len(), to_fetch
+        );
+
+        let client = self.client.clone();
+        let total = pending.len();
+        let mut blocks = futures::stream::iter(pending)
+            .map(|slot| {
+                let client = client.clone();
+                let is_produced = produced.contains(&slot);
+                async move {
+                    if is_produced {
+                        (slot, fetch_block_with_retry(&client, slot).await)
+                    } else {
+                        (slot, Ok(None))
                     }
                 }
-                self.last_processed_slot = slot;
+            })
+            .buffered(concurrency.max(1));
+
+        let mut done = 0;
+        let mut failed = 0;
+        while let Some((slot, block)) = blocks.next().await {
+            let result = match block {
+                Ok(block) => self.process_block(slot, block, sink, None, None).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                warn!("⚠️ Backfill failed for slot {}: {}", slot, e);
+                failed += 1;
+            }
+
+            done += 1;
+            if done % 100 == 0 {
+                info!("⏪ Backfill progress: {}/{} slots", done, total);
             }
         }
 
-        // Faster polling for real-time - 100ms instead of 200ms
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        self.flush(sink, None).await?;
+
+        if failed > 0 {
+            warn!("⚠️ Backfill finished with {} failed slots, re-run the same range to retry them", failed);
+        } else {
+            info!("✅ Backfill of slots {}..={} complete", from_slot, to_slot);
+        }
 
         Ok(())
     }
 
-    async fn process_single_slot_live(
-        &self, 
-        slot: u64, 
-        database: &Database,
-        live_tracker: &Arc<LiveTracker>,
+    /// Estimate the slot produced at `time` from slot timing, refined against `getBlockTime`
+    pub async fn slot_at_time(&self, time: DateTime<Utc>) -> Result<u64> {
+        let tip = self.client.get_slot().await.context("Failed to fetch current slot")?;
+
+        let behind_ms = (Utc::now() - time).num_milliseconds().max(0);
+        let mut slot = tip.saturating_sub((behind_ms / SLOT_DURATION_MS) as u64);
+
+        // Slot times drift from the 400ms target, so correct the estimate a few times
+        for _ in 0..8 {
+            let block_time = match self.client.get_block_time(slot).await {
+                Ok(block_time) => block_time,
+                // Skipped slot, try its neighbour
+                Err(_) => {
+                    slot = (slot + 1).min(tip);
+                    continue;
+                }
+            };
+
+            let drift_slots = (time.timestamp() - block_time) * 1000 / SLOT_DURATION_MS;
+            if drift_slots == 0 {
+                break;
+            }
+            slot = (slot as i64 + drift_slots).clamp(0, tip as i64) as u64;
+        }
+
+        Ok(slot)
+    }
+
+    /// Detect write locks in a block and commit its events. `None` means the slot was
+    /// skipped. Skipped and empty slots still advance `checkpoint` when one is given.
+    async fn process_block(
+        &mut self,
+        slot: u64,
+        block: Option<UiConfirmedBlock>,
+        sink: &dyn EventSink,
+        live_tracker: Option<&Arc<LiveTracker>>,
+        checkpoint: Option<&str>,
     ) -> Result<usize> {
-        let endpoint = self.rpc_endpoint.clone();
-        
-        // Get block with transaction details
-        let block = match tokio::task::spawn_blocking(move || {
-            let client = RpcClient::new_with_commitment(endpoint, CommitmentConfig::confirmed());
-            client.get_block_with_config(
-                slot,
-                solana_client::rpc_config::RpcBlockConfig {
-                    encoding: Some(UiTransactionEncoding::Base64),
-                    transaction_details: Some(
-                        solana_transaction_status::TransactionDetails::Full,
-                    ),
-                    rewards: Some(false),
-                    commitment: Some(CommitmentConfig::confirmed()),
-                    max_supported_transaction_version: Some(0),
-                },
-            )
-        }).await? {
-            Ok(block) => block,
-            Err(_) => {
+        let block = match block {
+            Some(block) => block,
+            None => {
+                let mut skipped = self.processor.process_skipped(slot);
+                self.attribute_leader(slot, &mut skipped.batch).await;
+                self.buffer_slot(slot, skipped.batch, sink, checkpoint).await?;
                 return Ok(0);
             }
         };
 
-        let mut detector = LockDetector::new();
-        let mut events = Vec::new();
-        
-        // Track per-account stats for live tracker
-        let mut account_stats: HashMap<String, (u32, i64, i64)> = HashMap::new(); // (count, sum_fee, max_fee)
-
-        if let Some(transactions) = block.transactions {
-            for tx_with_meta in &transactions {
-                if let Some(transaction) = &tx_with_meta.transaction.decode() {
-                    let signature = transaction.signatures[0].to_string();
-                    let message = &transaction.message;
-
-                    let writable_accounts: Vec<_> = message
-                        .static_account_keys()
-                        .iter()
-                        .enumerate()
-                        .filter(|(i, _)| message.is_maybe_writable(*i))
-                        .map(|(_, key)| *key)
-                        .collect();
-
-                    if !writable_accounts.is_empty() {
-                        detector.track_transaction(&signature, &writable_accounts);
-                    }
+        let mut processed = self.processor.process(slot, &block);
+        self.attribute_leader(slot, &mut processed.batch).await;
 
-                    let priority_fee = extract_priority_fee(tx_with_meta);
-                    let compute_units = extract_compute_units(tx_with_meta);
-                    let success = tx_with_meta.meta.as_ref()
-                        .and_then(|m| if m.status.is_ok() { Some(true) } else { None })
-                        .unwrap_or(false);
-
-                    // Extract program IDs from instructions
-                    let program_ids: Vec<String> = message.instructions().iter()
-                        .map(|ix| message.static_account_keys()[ix.program_id_index as usize].to_string())
-                        .collect();
-
-                    for account in &writable_accounts {
-                        let account_str = account.to_string();
-                        let contention = detector.calculate_contention(&account_str);
-                        let fee = priority_fee.unwrap_or(0);
-
-                        // Update per-account stats
-                        let entry = account_stats.entry(account_str.clone()).or_insert((0, 0, 0));
-                        entry.0 += 1; // tx count
-                        entry.1 += fee; // sum fees
-                        entry.2 = entry.2.max(fee); // max fee
-
-                        // Find first non-system program ID (more interesting)
-                        let program_id = program_ids.iter()
-                            .find(|p| *p != "11111111111111111111111111111111" && 
-                                      *p != "ComputeBudget111111111111111111111111111111")
-                            .cloned();
-
-                        let event = WriteLockEvent {
-                            time: Utc::now(),
-                            slot: slot as i64,
-                            account_pubkey: account_str,
-                            program_id,
-                            transaction_signature: signature.clone(),
-                            success,
-                            lock_contention_score: contention,
-                            priority_fee_lamports: priority_fee,
-                            compute_units_consumed: compute_units,
-                        };
-
-                        events.push(event);
-                    }
-                }
+        // Update live tracker with per-account contention data
+        if let Some(live_tracker) = live_tracker {
+            for (account, activity) in &processed.accounts {
+                live_tracker.record_slot(account, activity.contention).await;
             }
         }
 
-        // Update live tracker with per-account contention data
-        for (account, (tx_count, sum_fee, max_fee)) in account_stats {
-            let avg_fee = if tx_count > 0 { sum_fee / tx_count as i64 } else { 0 };
-            let contention = detector.calculate_contention(&account);
-            
-            live_tracker.record_slot(
-                &account,
-                slot,
-                contention,
-                tx_count,
-                avg_fee,
-                max_fee,
-            ).await;
-        }
+        let num_events = processed.batch.events.len();
+        self.buffer_slot(slot, processed.batch, sink, checkpoint).await?;
 
-        // Batch insert events
-        if !events.is_empty() {
-            info!("📝 Inserting {} events for slot {}", events.len(), slot);
-            database.insert_events(&events).await?;
+        Ok(num_events)
+    }
+
+    async fn attribute_leader(&mut self, slot: u64, batch: &mut SlotBatch) {
+        let leader = self.leaders.leader(slot).await;
+        for summary in batch.summaries.iter_mut() {
+            summary.leader = leader.clone();
         }
+    }
 
-        Ok(events.len())
+    /// Add a processed slot to the pending batch, flushing once it reaches `batch_size` rows
+    async fn buffer_slot(
+        &mut self,
+        slot: u64,
+        batch: SlotBatch,
+        sink: &dyn EventSink,
+        checkpoint: Option<&str>,
+    ) -> Result<()> {
+        self.pending.append(batch);
+        self.pending_through_slot = Some(self.pending_through_slot.map_or(slot, |s| s.max(slot)));
+
+        if self.pending.len() >= self.batch_size {
+            self.flush(sink, checkpoint).await?;
+        }
+        Ok(())
     }
 
-    #[allow(dead_code)]
-    pub async fn process_slot(&mut self, database: &Database) -> Result<()> {
-        let endpoint = self.rpc_endpoint.clone();
-        
-        let current_slot = tokio::task::spawn_blocking(move || {
-            let client = RpcClient::new_with_commitment(endpoint, CommitmentConfig::confirmed());
-            client.get_slot()
-        }).await??;
+    /// Commit the pending batch and advance `checkpoint` past it. On failure the batch
+    /// stays pending and is retried with the next flush.
+    async fn flush(&mut self, sink: &dyn EventSink, checkpoint: Option<&str>) -> Result<()> {
+        let Some(through_slot) = self.pending_through_slot else {
+            return Ok(());
+        };
 
-        if self.last_processed_slot == 0 {
-            self.last_processed_slot = current_slot.saturating_sub(5);
-            info!("🎯 Starting from slot {}", self.last_processed_slot);
+        if !self.pending.is_empty() {
+            info!(
+                "📝 Inserting {} events and {} conflicting reads through slot {}",
+                self.pending.events.len(), self.pending.reads.len(), through_slot
+            );
         }
+        sink.commit_slots(checkpoint, through_slot, &self.pending).await?;
 
-        if current_slot > self.last_processed_slot {
-            for slot in (self.last_processed_slot + 1)..=current_slot {
-                match self.process_single_slot(slot, database).await {
-                    Ok(num_events) => {
-                        if num_events > 0 {
-                            info!("✅ Slot {} processed: {} write lock events recorded", slot, num_events);
-                        }
-                    }
-                    Err(e) => {
-                        let err_str = e.to_string();
-                        if !err_str.contains("was skipped") && !err_str.contains("SlotSkipped") {
-                            warn!("⚠️ Error processing slot {}: {}", slot, e);
-                        }
-                    }
-                }
-                self.last_processed_slot = slot;
-            }
+        self.pending = SlotBatch::default();
+        self.pending_through_slot = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account_keys::AccountSource;
+    use crate::block_source::{fixture_dir, DirectoryBlockSource, MemoryBlockSource};
+    use crate::database::MemorySink;
+    use crate::failure_reason::FailureReason;
+    use lock_test_support::{Fault, MockChain, MockRpcServer, DEFAULT_LEADER};
+    use solana_sdk::pubkey::Pubkey;
+
+    fn test_stream() -> RpcStream {
+        stream_for("http://127.0.0.1:8899")
+    }
+
+    fn stream_for(rpc_endpoint: &str) -> RpcStream {
+        let config: Config = toml::from_str(&format!(r#"
+            rpc_endpoint = "{}"
+            database_url = "postgresql://localhost/unused"
+            poll_interval_ms = 10
+            rpc_timeout_secs = 1
+        "#, rpc_endpoint)).unwrap();
+        RpcStream::new(&config)
+    }
+
+    async fn fixture_node() -> MockRpcServer {
+        MockRpcServer::start(MockChain::from_fixture_dir(&fixture_dir()).unwrap()).await
+    }
+
+    async fn wait_for_checkpoint(sink: &MemorySink, slot: u64) {
+        while sink.checkpoints.lock().unwrap().get(CHECKPOINT_NAME) != Some(&slot) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
         }
+    }
 
-        tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    /// Fixture accounts are `[n; 32]`
+    fn key(n: u8) -> String {
+        Pubkey::new_from_array([n; 32]).to_string()
+    }
 
-        Ok(())
+    #[tokio::test]
+    async fn test_fixture_blocks_end_to_end() {
+        let mut stream = test_stream();
+        let mut source = DirectoryBlockSource::open(&fixture_dir()).unwrap();
+        let sink = MemorySink::default();
+        let live_tracker = Arc::new(LiveTracker::new(10));
+
+        stream.consume(&mut source, &sink, Some(&live_tracker), Some("test")).await.unwrap();
+
+        let events = sink.events.lock().unwrap().clone();
+        assert_eq!(events.len(), 11);
+        assert_eq!(sink.checkpoints.lock().unwrap()["test"], 300_000_002);
+
+        // The pool is write-locked twice in the first block (the second attempt fails)
+        // and once in the last. Both writers of the first block see the slot total.
+        let pool: Vec<_> = events.iter()
+            .filter(|e| e.account_pubkey == key(1))
+            .map(|e| (e.slot, e.transaction_index, e.lock_contention_score, e.queue_position, e.success))
+            .collect();
+        assert_eq!(pool, vec![
+            (300_000_000, 0, 2.0, 1, Some(true)),
+            (300_000_000, 1, 2.0, 2, Some(false)),
+            (300_000_002, 0, 1.0, 1, Some(true)),
+        ]);
+
+        // Each event carries the fee payer of its transaction
+        let payers: Vec<_> = events.iter()
+            .filter(|e| e.account_pubkey == key(1) && e.slot == 300_000_000)
+            .map(|e| e.fee_payer.clone())
+            .collect();
+        assert_eq!(payers, vec![key(11), key(12)]);
+
+        // No logs to explain the failed swap's error code
+        let failed = events.iter().find(|e| e.success == Some(false)).unwrap();
+        assert_eq!(failed.failure_reason, Some(FailureReason::CustomError));
+        assert_eq!((failed.error_instruction_index, failed.error_code), (Some(2), Some(6001)));
+
+        // Both pool writers consumed CU in the first block, failed or not
+        assert!(events.iter()
+            .filter(|e| e.account_pubkey == key(1) && e.slot == 300_000_000)
+            .all(|e| e.account_compute_units == 75_000));
+
+        let loaded = events.iter().find(|e| e.account_pubkey == key(3)).unwrap();
+        assert_eq!(loaded.account_source, AccountSource::LookupTable);
+        assert_eq!(loaded.program_id, Some(key(9)));
+
+        // No blockTime in the last block: two slots after the first one's
+        let last = events.iter().find(|e| e.slot == 300_000_002).unwrap();
+        assert!(last.time_estimated);
+        assert_eq!(last.time.timestamp_millis(), 1_717_000_000_800);
+
+        // The two pool writers form one chain, the third transaction is independent.
+        // No graph for the skipped slot.
+        let graphs = sink.conflict_graphs.lock().unwrap().clone();
+        assert_eq!(graphs.len(), 2);
+        let first = &graphs[0];
+        assert_eq!((first.slot, first.transaction_count, first.component_count), (300_000_000, 3, 2));
+        assert_eq!((first.longest_chain, first.parallelism_factor), (2, 1.5));
+        assert_eq!((first.bottleneck_account.clone(), first.bottleneck_writers), (Some(key(1)), 2));
+
+        // One summary per slot, the skipped one included
+        let summaries = sink.summaries.lock().unwrap().clone();
+        let totals: Vec<_> = summaries.iter()
+            .map(|s| (s.slot, s.skipped, s.transaction_count, s.failed_transaction_count, s.total_compute_units, s.total_fees_lamports))
+            .collect();
+        assert_eq!(totals, vec![
+            (300_000_000, false, 3, 1, 135_000, 32_000),
+            (300_000_001, true, 0, 0, 0, 0),
+            (300_000_002, false, 2, 0, 45_000, 14_000),
+        ]);
+        assert!(summaries[1].time_estimated);
+        assert_eq!(summaries[1].time.timestamp_millis(), 1_717_000_000_400);
+
+        let hot_accounts = live_tracker.get_hot_accounts(usize::MAX).await;
+        let hot = hot_accounts.iter().find(|hot| hot.account == key(1)).unwrap();
+        // Two writers in the first slot, one in the second
+        assert_eq!(hot.avg_contention, 1.5);
     }
 
-    #[allow(dead_code)]
-    async fn process_single_slot(&self, slot: u64, database: &Database) -> Result<usize> {
-        let endpoint = self.rpc_endpoint.clone();
-        
-        let block = match tokio::task::spawn_blocking(move || {
-            let client = RpcClient::new_with_commitment(endpoint, CommitmentConfig::confirmed());
-            client.get_block_with_config(
-                slot,
-                solana_client::rpc_config::RpcBlockConfig {
-                    encoding: Some(UiTransactionEncoding::Base64),
-                    transaction_details: Some(
-                        solana_transaction_status::TransactionDetails::Full,
-                    ),
-                    rewards: Some(false),
-                    commitment: Some(CommitmentConfig::confirmed()),
-                    max_supported_transaction_version: Some(0),
-                },
-            )
-        }).await? {
-            Ok(block) => block,
-            Err(_) => {
-                return Ok(0);
-            }
-        };
+    #[tokio::test]
+    async fn test_skipped_slots_advance_checkpoint() {
+        let mut stream = test_stream();
+        let mut source = MemoryBlockSource::new(vec![(500, None), (501, None)]);
+        let sink = MemorySink::default();
 
-        let mut detector = LockDetector::new();
-        let mut events = Vec::new();
-
-        if let Some(transactions) = block.transactions {
-            for tx_with_meta in &transactions {
-                if let Some(transaction) = &tx_with_meta.transaction.decode() {
-                    let signature = transaction.signatures[0].to_string();
-                    let message = &transaction.message;
-
-                    let writable_accounts: Vec<_> = message
-                        .static_account_keys()
-                        .iter()
-                        .enumerate()
-                        .filter(|(i, _)| message.is_maybe_writable(*i))
-                        .map(|(_, key)| *key)
-                        .collect();
-
-                    if !writable_accounts.is_empty() {
-                        detector.track_transaction(&signature, &writable_accounts);
-                    }
+        stream.consume(&mut source, &sink, None, Some("test")).await.unwrap();
 
-                    let priority_fee = extract_priority_fee(tx_with_meta);
-                    let compute_units = extract_compute_units(tx_with_meta);
-                    let success = tx_with_meta.meta.as_ref()
-                        .and_then(|m| if m.status.is_ok() { Some(true) } else { None })
-                        .unwrap_or(false);
-
-                    for account in &writable_accounts {
-                        let account_str = account.to_string();
-                        let contention = detector.calculate_contention(&account_str);
-
-                        let event = WriteLockEvent {
-                            time: Utc::now(),
-                            slot: slot as i64,
-                            account_pubkey: account_str,
-                            program_id: None,
-                            transaction_signature: signature.clone(),
-                            success,
-                            lock_contention_score: contention,
-                            priority_fee_lamports: priority_fee,
-                            compute_units_consumed: compute_units,
-                        };
-
-                        events.push(event);
-                    }
-                }
+        assert!(sink.events.lock().unwrap().is_empty());
+        assert_eq!(sink.checkpoints.lock().unwrap()["test"], 501);
+    }
+
+    #[tokio::test]
+    async fn test_run_follows_mock_node_through_faults() {
+        let server = fixture_node().await;
+        // Rate limits are retried by the HTTP client, the slow response times out and
+        // is retried by the fetch stage
+        server.inject("getBlock", Fault::RateLimited, 2);
+        server.inject("getBlock", Fault::Slow(Duration::from_secs(3)), 1);
+
+        let mut stream = stream_for(&server.url());
+        let sink = MemorySink::default();
+        sink.checkpoints.lock().unwrap().insert(CHECKPOINT_NAME.to_string(), 299_999_999);
+        let live_tracker = Arc::new(LiveTracker::new(10));
+
+        tokio::time::timeout(Duration::from_secs(10), async {
+            tokio::select! {
+                result = stream.run(&sink, &live_tracker) => panic!("run stopped: {:?}", result),
+                _ = wait_for_checkpoint(&sink, 300_000_002) => {}
             }
-        }
+        }).await.expect("indexer didn't reach the tip");
 
-        if !events.is_empty() {
-            info!("📝 Inserting {} events for slot {}", events.len(), slot);
-            database.insert_events(&events).await?;
-        }
+        assert_eq!(sink.events.lock().unwrap().len(), 11);
+        // Two produced blocks, three faulted attempts, and no fetch of the skipped slot
+        assert_eq!(server.calls("getBlock"), 5);
 
-        Ok(events.len())
+        // Leaders come from one getSlotLeaders call, skipped slot included
+        let leaders: Vec<_> = sink.summaries.lock().unwrap().iter().map(|s| s.leader.clone()).collect();
+        assert_eq!(leaders, vec![Some(DEFAULT_LEADER.to_string()); 3]);
+        assert_eq!(server.calls("getSlotLeaders"), 1);
     }
-}
 
-/// Extract priority fee from transaction metadata
-fn extract_priority_fee(tx: &EncodedTransactionWithStatusMeta) -> Option<i64> {
-    tx.meta.as_ref().map(|m| m.fee as i64)
-}
+    #[tokio::test]
+    async fn test_leader_lookup_failures_dont_hold_up_processing() {
+        let server = fixture_node().await;
+        server.inject("getSlotLeaders", Fault::ServerError, 1);
+        let mut stream = stream_for(&server.url());
+        let mut source = DirectoryBlockSource::open(&fixture_dir()).unwrap();
+        let sink = MemorySink::default();
+
+        stream.consume(&mut source, &sink, None, Some(CHECKPOINT_NAME)).await.unwrap();
+
+        assert_eq!(sink.checkpoints.lock().unwrap().get(CHECKPOINT_NAME), Some(&300_000_002));
+        assert_eq!(sink.events.lock().unwrap().len(), 11);
+        let leaders: Vec<_> = sink.summaries.lock().unwrap().iter().map(|s| s.leader.clone()).collect();
+        assert_eq!(leaders, vec![None; 3]);
+        // The failed chunk isn't requested again for every slot
+        assert_eq!(server.calls("getSlotLeaders"), 1);
+    }
 
-/// Extract compute units from transaction metadata
-fn extract_compute_units(tx: &EncodedTransactionWithStatusMeta) -> Option<i32> {
-    use solana_transaction_status::option_serializer::OptionSerializer;
-    tx.meta.as_ref().and_then(|m| match m.compute_units_consumed {
-        OptionSerializer::Some(cu) => Some(cu as i32),
-        _ => None,
-    })
-}
+    #[tokio::test]
+    async fn test_backfill_looks_up_leaders_once_per_epoch() {
+        let server = fixture_node().await;
+        let mut stream = stream_for(&server.url()).with_leader_lookup(LeaderLookup::EpochSchedule);
+        let sink = MemorySink::default();
+
+        // The node still has the schedule of the tip's epoch
+        stream.backfill(300_000_000, 300_000_002, 4, &sink).await.unwrap();
+        let leaders: Vec<_> = sink.summaries.lock().unwrap().iter().map(|s| s.leader.clone()).collect();
+        assert_eq!(leaders, vec![Some(DEFAULT_LEADER.to_string()); 3]);
+        assert_eq!(server.calls("getLeaderSchedule"), 1);
+
+        // Two epochs later it doesn't, and the slots are indexed without leaders
+        server.set_tip(301_000_000);
+        let mut stream = stream_for(&server.url()).with_leader_lookup(LeaderLookup::EpochSchedule);
+        let sink = MemorySink::default();
+        stream.backfill(300_000_000, 300_000_002, 4, &sink).await.unwrap();
+        let leaders: Vec<_> = sink.summaries.lock().unwrap().iter().map(|s| s.leader.clone()).collect();
+        assert_eq!(leaders, vec![None; 3]);
+        assert_eq!(sink.events.lock().unwrap().len(), 11);
+        assert_eq!(server.calls("getLeaderSchedule"), 2);
+        assert_eq!(server.calls("getSlotLeaders"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_backfill_skips_indexed_slots() {
+        let server = fixture_node().await;
+        let mut stream = stream_for(&server.url());
+        let sink = MemorySink::default();
+
+        stream.backfill(300_000_000, 300_000_002, 4, &sink).await.unwrap();
+        assert_eq!(sink.events.lock().unwrap().len(), 11);
+        assert_eq!(server.calls("getBlock"), 2);
+        // The skipped slot is recorded without being fetched
+        assert_eq!(sink.summaries.lock().unwrap().len(), 3);
+
+        stream.backfill(300_000_000, 300_000_002, 4, &sink).await.unwrap();
+        assert_eq!(server.calls("getBlock"), 2);
+        assert_eq!(sink.summaries.lock().unwrap().len(), 3);
+    }
 
+    #[tokio::test]
+    async fn test_rewriting_backfilled_slots_replaces_their_rows() {
+        let server = fixture_node().await;
+        let mut stream = stream_for(&server.url());
+        let sink = MemorySink::default();
+        let rows = |sink: &MemorySink| {
+            let mut rows: Vec<_> = sink.events.lock().unwrap().iter()
+                .map(|e| (e.slot, e.transaction_signature.clone(), e.account_pubkey.clone()))
+                .collect();
+            rows.sort();
+            rows
+        };
+
+        stream.backfill(300_000_000, 300_000_002, 4, &sink).await.unwrap();
+        let backfilled = rows(&sink);
+
+        // Replaying the same blocks doesn't skip indexed slots, it writes them again
+        let mut source = DirectoryBlockSource::open(&fixture_dir()).unwrap();
+        stream.consume(&mut source, &sink, None, None).await.unwrap();
+
+        assert_eq!(rows(&sink), backfilled);
+        assert_eq!(sink.summaries.lock().unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,255 @@
+use anyhow::{Context, Result};
+use futures::{Stream, StreamExt};
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcBlockSubscribeConfig, RpcBlockSubscribeFilter};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_transaction_status::{TransactionDetails, UiTransactionEncoding};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use crate::pipeline::{discover_slots, discover_slots_through, get_produced_slots, slot_jobs, SlotJob};
+
+/// A subscription that has been silent this long is treated as dropped
+const NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long discovery falls back to polling before trying to resubscribe
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(30);
+
+/// Pubsub method used to learn about new slots
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscriptionKind {
+    /// `slotSubscribe`: each notification triggers a `getBlocks` call
+    Slot,
+    /// `blockSubscribe`: confirmed blocks arrive with the notification
+    Block,
+}
+
+/// Stage 1, push-based: emit slots as the node announces them over websocket instead
+/// of polling `getSlot` on a timer. While the subscription is down, discovery polls
+/// like `spawn_slot_discovery` and resubscribes after `RESUBSCRIBE_DELAY`.
+/// Exits when the receiver is dropped.
+pub fn spawn_subscription_discovery(
+    client: Arc<RpcClient>,
+    ws_endpoint: String,
+    kind: SubscriptionKind,
+    start_after: u64,
+    poll_interval: Duration,
+    jobs: mpsc::Sender<SlotJob>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut next_slot = start_after + 1;
+
+        loop {
+            match follow_subscription(&client, &ws_endpoint, kind, &mut next_slot, &jobs).await {
+                Ok(()) => return,
+                Err(e) => warn!("⚠️ {:?} subscription dropped, falling back to polling: {:#}", kind, e),
+            }
+
+            let resubscribe_at = Instant::now() + RESUBSCRIBE_DELAY;
+            while Instant::now() < resubscribe_at {
+                match discover_slots(&client, next_slot).await {
+                    Ok(new_jobs) => {
+                        if !send_jobs(new_jobs, &mut next_slot, &jobs).await {
+                            return;
+                        }
+                    }
+                    Err(e) => warn!("⚠️ Slot discovery failed: {:#}", e),
+                }
+
+                tokio::time::sleep(poll_interval).await;
+            }
+
+            info!("🔌 Resubscribing to {}", ws_endpoint);
+        }
+    })
+}
+
+/// Emit jobs from a subscription until it fails. Returns `Ok` once the receiver is dropped.
+async fn follow_subscription(
+    client: &RpcClient,
+    ws_endpoint: &str,
+    kind: SubscriptionKind,
+    next_slot: &mut u64,
+    jobs: &mpsc::Sender<SlotJob>,
+) -> Result<()> {
+    let pubsub = PubsubClient::new(ws_endpoint).await
+        .with_context(|| format!("Failed to connect to {}", ws_endpoint))?;
+
+    match kind {
+        SubscriptionKind::Slot => {
+            let (mut notifications, _unsubscribe) = pubsub.slot_subscribe().await
+                .context("slotSubscribe failed")?;
+            info!("🔌 Subscribed to slot notifications");
+
+            loop {
+                let slot = next_notification(&mut notifications).await?.slot;
+                let new_jobs = discover_slots_through(client, *next_slot, slot).await?;
+                if !send_jobs(new_jobs, next_slot, jobs).await {
+                    return Ok(());
+                }
+            }
+        }
+        SubscriptionKind::Block => {
+            let config = RpcBlockSubscribeConfig {
+                commitment: Some(CommitmentConfig::confirmed()),
+                encoding: Some(UiTransactionEncoding::Base64),
+                transaction_details: Some(TransactionDetails::Full),
+                show_rewards: Some(false),
+                max_supported_transaction_version: Some(0),
+            };
+            let (mut notifications, _unsubscribe) = pubsub
+                .block_subscribe(RpcBlockSubscribeFilter::All, Some(config))
+                .await
+                .context("blockSubscribe failed (is it enabled on the node?)")?;
+            info!("🔌 Subscribed to block notifications");
+
+            loop {
+                let update = next_notification(&mut notifications).await?.value;
+                if update.slot < *next_slot {
+                    continue;
+                }
+
+                // The block's ancestors are confirmed too, so any slot in the gap that
+                // `getBlocks` doesn't list was skipped
+                let mut new_jobs = Vec::new();
+                if update.slot > *next_slot {
+                    let produced = get_produced_slots(client, *next_slot, update.slot - 1).await?;
+                    new_jobs = slot_jobs(*next_slot, update.slot - 1, &produced);
+                }
+                new_jobs.push(match update.block {
+                    Some(block) => SlotJob::Prefetched(update.slot, Box::new(block)),
+                    // The node couldn't serialize the block, fetch it instead
+                    None => SlotJob::Fetch(update.slot),
+                });
+
+                if !send_jobs(new_jobs, next_slot, jobs).await {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+async fn next_notification<T>(notifications: &mut (impl Stream<Item = T> + Unpin)) -> Result<T> {
+    match tokio::time::timeout(NOTIFICATION_TIMEOUT, notifications.next()).await {
+        Ok(Some(notification)) => Ok(notification),
+        Ok(None) => anyhow::bail!("Subscription closed by the server"),
+        Err(_) => anyhow::bail!("No notification for {}s", NOTIFICATION_TIMEOUT.as_secs()),
+    }
+}
+
+/// Forward jobs in order, advancing `next_slot`. Returns false once the receiver is dropped.
+async fn send_jobs(new_jobs: Vec<SlotJob>, next_slot: &mut u64, jobs: &mpsc::Sender<SlotJob>) -> bool {
+    for job in new_jobs {
+        *next_slot = job.slot() + 1;
+        if jobs.send(job).await.is_err() {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::MAX_GET_BLOCKS_RANGE;
+    use lock_test_support::{MockChain, MockRpcServer};
+    use serde_json::json;
+
+    fn client(server: &MockRpcServer) -> Arc<RpcClient> {
+        Arc::new(RpcClient::new_with_commitment(server.url(), CommitmentConfig::confirmed()))
+    }
+
+    async fn collect(mut rx: mpsc::Receiver<SlotJob>, count: usize) -> Vec<(u64, &'static str)> {
+        let mut received = Vec::new();
+        while received.len() < count {
+            let job = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await
+                .expect("timed out waiting for slot jobs")
+                .expect("discovery stopped");
+            let kind = match job {
+                SlotJob::Fetch(_) => "fetch",
+                SlotJob::Skipped(_) => "skipped",
+                SlotJob::Prefetched(..) => "prefetched",
+            };
+            received.push((job.slot(), kind));
+        }
+        received
+    }
+
+    fn block(slot: u64) -> serde_json::Value {
+        json!({
+            "previousBlockhash": "11111111111111111111111111111111",
+            "blockhash": "11111111111111111111111111111111",
+            "parentSlot": slot - 1,
+            "transactions": [],
+            "blockTime": null,
+            "blockHeight": null,
+        })
+    }
+
+    fn chain(produced: &[u64], skipped: &[u64]) -> MockChain {
+        let chain = produced.iter().fold(MockChain::new(), |chain, slot| chain.with_block(*slot, block(*slot)));
+        skipped.iter().fold(chain, |chain, slot| chain.with_skipped(*slot))
+    }
+
+    #[tokio::test]
+    async fn test_slot_subscription_falls_back_to_polling() {
+        let server = MockRpcServer::start(
+            chain(&[101, 102, 104, 105, 107, 108], &[103, 106]).with_notifications(vec![
+                json!({ "slot": 102, "parent": 101, "root": 70 }),
+                json!({ "slot": 105, "parent": 104, "root": 71 }),
+            ]),
+        ).await;
+
+        let (tx, rx) = mpsc::channel(16);
+        spawn_subscription_discovery(client(&server), server.ws_url(), SubscriptionKind::Slot, 100, Duration::from_millis(10), tx);
+
+        // 101..=105 from notifications, then 106..=108 from polling after the socket closes
+        assert_eq!(collect(rx, 8).await, vec![
+            (101, "fetch"), (102, "fetch"), (103, "skipped"), (104, "fetch"), (105, "fetch"),
+            (106, "skipped"), (107, "fetch"), (108, "fetch"),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_block_subscription_fills_gaps() {
+        let notification = |slot: u64| json!({
+            "context": { "slot": slot },
+            "value": { "slot": slot, "block": block(slot), "err": null },
+        });
+        let server = MockRpcServer::start(
+            chain(&[101, 102, 104], &[103]).with_notifications(vec![notification(102), notification(104)]),
+        ).await;
+
+        let (tx, rx) = mpsc::channel(16);
+        spawn_subscription_discovery(client(&server), server.ws_url(), SubscriptionKind::Block, 100, Duration::from_millis(10), tx);
+
+        assert_eq!(collect(rx, 4).await, vec![
+            (101, "fetch"), (102, "prefetched"), (103, "skipped"), (104, "prefetched"),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_block_subscription_fills_gaps_wider_than_get_blocks_accepts() {
+        let tip = 100 + MAX_GET_BLOCKS_RANGE + 10;
+        let server = MockRpcServer::start(
+            chain(&[101, tip - 1, tip], &[]).with_notifications(vec![json!({
+                "context": { "slot": tip },
+                "value": { "slot": tip, "block": block(tip), "err": null },
+            })]),
+        ).await;
+
+        let (tx, rx) = mpsc::channel(1024);
+        spawn_subscription_discovery(client(&server), server.ws_url(), SubscriptionKind::Block, 100, Duration::from_millis(10), tx);
+
+        let jobs = collect(rx, (tip - 100) as usize).await;
+        let fetched: Vec<_> = jobs.iter().filter(|(_, kind)| *kind != "skipped").collect();
+        assert_eq!(fetched, vec![&(101, "fetch"), &(tip - 1, "fetch"), &(tip, "prefetched")]);
+        assert_eq!(server.calls("getBlocks"), 2);
+    }
+}
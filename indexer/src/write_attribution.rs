@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use solana_sdk::{message::VersionedMessage, pubkey::Pubkey};
+use solana_transaction_status::{option_serializer::OptionSerializer, UiInstruction, UiTransactionStatusMeta};
+
+use crate::account_keys::TransactionAccount;
+
+/// Programs that move lamports and tokens or set budgets on behalf of the program
+/// calling them. An account they share with any other program is credited to that one.
+const UTILITY_PROGRAMS: &[&str] = &[
+    "11111111111111111111111111111111",
+    "ComputeBudget111111111111111111111111111111",
+    "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+    "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb",
+    "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL",
+    "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr",
+];
+
+/// Stack height of a top-level instruction; CPIs start at 2
+const TOP_LEVEL_STACK_HEIGHT: u32 = 1;
+
+/// Program an account is credited to within one transaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribution {
+    /// Innermost non-utility program whose instruction references the account
+    pub program_id: Pubkey,
+    /// Program of the top-level instruction that led there; differs from
+    /// `program_id` when the account is only reached through a CPI
+    pub invoking_program_id: Pubkey,
+    /// Index of that top-level instruction
+    pub instruction_index: u8,
+}
+
+/// Attribute every account referenced by an instruction, inner instructions
+/// included, to the program that most likely locks it. `accounts` must be in
+/// runtime order, as returned by `resolve_accounts`. Accounts no instruction
+/// references, like a bare fee payer, are left out.
+pub fn attribute_accounts(
+    message: &VersionedMessage,
+    meta: Option<&UiTransactionStatusMeta>,
+    accounts: &[TransactionAccount],
+) -> HashMap<Pubkey, Attribution> {
+    let key = |index: u8| accounts.get(index as usize).map(|account| account.pubkey);
+    let mut attributed: HashMap<Pubkey, ((bool, u32), Attribution)> = HashMap::new();
+
+    let mut credit = |program: Pubkey, stack_height: u32, instruction_index: u8, invoking: Pubkey, indexes: &[u8]| {
+        // Specific programs beat utility ones, deeper calls beat the callers passing accounts through
+        let rank = (!UTILITY_PROGRAMS.contains(&program.to_string().as_str()), stack_height);
+        for account in indexes.iter().filter_map(|&i| key(i)) {
+            if attributed.get(&account).is_none_or(|(best, _)| rank > *best) {
+                let attribution = Attribution { program_id: program, invoking_program_id: invoking, instruction_index };
+                attributed.insert(account, (rank, attribution));
+            }
+        }
+    };
+
+    let instructions = message.instructions();
+    for (i, ix) in instructions.iter().enumerate() {
+        if let Some(program) = key(ix.program_id_index) {
+            credit(program, TOP_LEVEL_STACK_HEIGHT, i as u8, program, &ix.accounts);
+        }
+    }
+
+    if let Some(OptionSerializer::Some(inner)) = meta.map(|m| &m.inner_instructions) {
+        for group in inner {
+            let Some(invoking) = instructions.get(group.index as usize).and_then(|ix| key(ix.program_id_index)) else {
+                continue;
+            };
+            for ix in &group.instructions {
+                // Parsed instructions only come with jsonParsed encoding, which the indexer doesn't request
+                let UiInstruction::Compiled(ix) = ix else { continue };
+                if let Some(program) = key(ix.program_id_index) {
+                    let stack_height = ix.stack_height.unwrap_or(TOP_LEVEL_STACK_HEIGHT + 1);
+                    credit(program, stack_height, group.index, invoking, &ix.accounts);
+                }
+            }
+        }
+    }
+
+    attributed.into_iter().map(|(account, (_, attribution))| (account, attribution)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account_keys::resolve_accounts;
+    use solana_sdk::{hash::Hash, instruction::CompiledInstruction, message::Message};
+
+    fn meta_with_inner(inner: serde_json::Value) -> UiTransactionStatusMeta {
+        serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "innerInstructions": inner,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_cpi_targets_are_credited_to_the_callee() {
+        let token_program: Pubkey = UTILITY_PROGRAMS[2].parse().unwrap();
+        let (payer, pool, vault, user_ata) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let (router, amm) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        // The router passes everything to the AMM, which moves tokens out of its vault
+        let keys = vec![payer, pool, vault, user_ata, router, amm, token_program];
+        let message = VersionedMessage::Legacy(Message::new_with_compiled_instructions(
+            1, 0, 3, keys, Hash::default(),
+            vec![CompiledInstruction::new_from_raw_parts(4, Vec::new(), vec![0, 1, 2, 3, 5, 6])],
+        ));
+        let meta = meta_with_inner(serde_json::json!([{
+            "index": 0,
+            "instructions": [
+                { "programIdIndex": 5, "accounts": [1, 2, 3, 6], "data": "", "stackHeight": 2 },
+                { "programIdIndex": 6, "accounts": [2, 3, 1], "data": "", "stackHeight": 3 },
+            ],
+        }]));
+
+        let accounts = resolve_accounts(&message, Some(&meta));
+        let attributed = attribute_accounts(&message, Some(&meta), &accounts);
+
+        for account in [pool, vault, user_ata] {
+            assert_eq!(attributed[&account], Attribution {
+                program_id: amm,
+                invoking_program_id: router,
+                instruction_index: 0,
+            });
+        }
+        assert_eq!(attributed[&payer].program_id, router);
+    }
+
+    #[test]
+    fn test_unreferenced_accounts_are_not_attributed() {
+        let (payer, pool, program) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let message = VersionedMessage::Legacy(Message::new_with_compiled_instructions(
+            1, 0, 1, vec![payer, pool, program], Hash::default(),
+            vec![CompiledInstruction::new_from_raw_parts(2, Vec::new(), vec![1])],
+        ));
+
+        let accounts = resolve_accounts(&message, None);
+        let attributed = attribute_accounts(&message, None, &accounts);
+
+        assert_eq!(attributed.len(), 1);
+        assert_eq!(attributed[&pool].program_id, program);
+        assert_eq!(attributed[&pool].invoking_program_id, program);
+    }
+}
@@ -0,0 +1,4 @@
+-- Write sets now include accounts loaded through address lookup tables (v0 transactions).
+-- Record where each account came from: 'static' (message account keys) or 'lookup_table'.
+ALTER TABLE write_lock_events
+    ADD COLUMN account_source TEXT NOT NULL DEFAULT 'static';
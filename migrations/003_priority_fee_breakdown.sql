@@ -0,0 +1,17 @@
+-- priority_fee_lamports used to hold meta.fee (base signature fee included).
+-- It now holds only the ComputeBudget component (CU price * CU limit);
+-- the individual inputs and the base fee are stored alongside it.
+ALTER TABLE write_lock_events
+    ADD COLUMN compute_unit_price_micro_lamports BIGINT,
+    ADD COLUMN compute_unit_limit INTEGER,
+    ADD COLUMN base_fee_lamports BIGINT;
+
+-- Rows written before this migration cannot be split into base and priority
+-- components. Their values move to legacy_priority_fee_lamports, which no query
+-- reads, so they stay out of estimates without being lost.
+ALTER TABLE write_lock_events ADD COLUMN legacy_priority_fee_lamports BIGINT;
+
+UPDATE write_lock_events
+SET legacy_priority_fee_lamports = priority_fee_lamports,
+    priority_fee_lamports = NULL
+WHERE priority_fee_lamports IS NOT NULL;
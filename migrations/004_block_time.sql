@@ -0,0 +1,9 @@
+-- `time` is now the block's block_time (or an estimate from slot timing when the
+-- RPC has none) instead of the moment the indexer processed it.
+-- ingested_at keeps the wall-clock processing time so indexer lag can be measured.
+ALTER TABLE write_lock_events
+    ADD COLUMN time_estimated BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN ingested_at TIMESTAMPTZ,
+    ADD COLUMN transaction_index INTEGER;
+
+CREATE INDEX idx_slot_tx_index ON write_lock_events (slot, transaction_index);
@@ -0,0 +1,7 @@
+-- Last fully committed slot per indexer stream. Updated in the same transaction
+-- as the slot's events so a restart can resume without leaving a hole.
+CREATE TABLE indexer_checkpoints (
+    name TEXT PRIMARY KEY,
+    slot BIGINT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
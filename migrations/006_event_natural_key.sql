@@ -0,0 +1,24 @@
+-- Natural key for write lock events so replays, backfills and retries upsert
+-- instead of double counting. TimescaleDB requires the partitioning column in
+-- unique indexes; `time` is the block time, so it is fixed per slot and the key
+-- is effectively (slot, transaction_signature, account_pubkey).
+
+-- Drop duplicates left behind by slots that were processed more than once
+DELETE FROM write_lock_events
+WHERE (tableoid, ctid) IN (
+    SELECT tableoid, ctid
+    FROM (
+        SELECT
+            tableoid,
+            ctid,
+            ROW_NUMBER() OVER (
+                PARTITION BY slot, transaction_signature, account_pubkey
+                ORDER BY created_at DESC
+            ) AS rn
+        FROM write_lock_events
+    ) ranked
+    WHERE rn > 1
+);
+
+CREATE UNIQUE INDEX idx_event_natural_key
+    ON write_lock_events (slot, transaction_signature, account_pubkey, time);
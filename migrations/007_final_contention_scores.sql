@@ -0,0 +1,17 @@
+-- lock_contention_score used to be a running count taken while the block was
+-- still being processed, so it was each event's position among the writers of
+-- the account (1, 2, 3...). It is now the slot total (the queue size) and the
+-- position is stored separately in queue_position (1-based, in block order).
+ALTER TABLE write_lock_events ADD COLUMN queue_position INTEGER;
+
+-- Existing rows: the old score is the position and its slot maximum the size
+UPDATE write_lock_events e
+SET queue_position = e.lock_contention_score::INTEGER,
+    lock_contention_score = totals.queue_size
+FROM (
+    SELECT slot, account_pubkey, MAX(lock_contention_score) AS queue_size
+    FROM write_lock_events
+    GROUP BY slot, account_pubkey
+) totals
+WHERE e.slot = totals.slot
+  AND e.account_pubkey = totals.account_pubkey;
@@ -0,0 +1,37 @@
+-- The scheduler blocks a writer on readers of the same account too, so read
+-- locks are tracked alongside write locks.
+
+-- Transactions read-locking the account in the same slot as this write
+ALTER TABLE write_lock_events
+    ADD COLUMN read_conflicts INTEGER NOT NULL DEFAULT 0;
+
+-- Read locks on accounts that are also write-locked in the slot. Reads of
+-- accounts nobody writes don't conflict with anything and are not stored.
+CREATE TABLE read_lock_events (
+    time TIMESTAMPTZ NOT NULL,
+    time_estimated BOOLEAN NOT NULL DEFAULT false,
+    ingested_at TIMESTAMPTZ,
+    slot BIGINT NOT NULL,
+    transaction_index INTEGER,
+
+    account_pubkey TEXT NOT NULL,
+    account_source TEXT NOT NULL DEFAULT 'static',
+    program_id TEXT,
+
+    transaction_signature TEXT NOT NULL,
+    success BOOLEAN NOT NULL,
+
+    -- Transactions write-locking the account in this slot
+    writer_count INTEGER NOT NULL,
+
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+SELECT create_hypertable('read_lock_events', 'time');
+
+CREATE INDEX idx_read_account_time ON read_lock_events (account_pubkey, time DESC);
+CREATE INDEX idx_read_slot ON read_lock_events (slot);
+CREATE UNIQUE INDEX idx_read_natural_key
+    ON read_lock_events (slot, transaction_signature, account_pubkey, time);
+
+SELECT add_retention_policy('read_lock_events', INTERVAL '7 days');
@@ -0,0 +1,26 @@
+-- Per-block transaction conflict graph: two transactions are connected when
+-- they write-lock a common account.
+CREATE TABLE slot_conflict_graphs (
+    time TIMESTAMPTZ NOT NULL,
+    slot BIGINT NOT NULL,
+
+    transaction_count INTEGER NOT NULL,
+    component_count INTEGER NOT NULL,
+    largest_component INTEGER NOT NULL,
+    -- Longest run of transactions that each wait on an earlier writer
+    longest_chain INTEGER NOT NULL,
+    -- transaction_count / longest_chain
+    parallelism_factor REAL NOT NULL,
+
+    -- Account with the most writers in the block
+    bottleneck_account TEXT,
+    bottleneck_writers INTEGER NOT NULL,
+
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+SELECT create_hypertable('slot_conflict_graphs', 'time');
+
+CREATE UNIQUE INDEX idx_conflict_graph_slot ON slot_conflict_graphs (slot, time);
+
+SELECT add_retention_policy('slot_conflict_graphs', INTERVAL '7 days');
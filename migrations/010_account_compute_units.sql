@@ -0,0 +1,15 @@
+-- Solana caps the compute units all transactions writing an account may consume
+-- per block. Every event now carries the slot total for its account, so
+-- saturation against that cap can be read off any row.
+ALTER TABLE write_lock_events
+    ADD COLUMN account_compute_units BIGINT NOT NULL DEFAULT 0;
+
+UPDATE write_lock_events e
+SET account_compute_units = totals.compute_units
+FROM (
+    SELECT slot, account_pubkey, COALESCE(SUM(compute_units_consumed), 0) AS compute_units
+    FROM write_lock_events
+    GROUP BY slot, account_pubkey
+) totals
+WHERE e.slot = totals.slot
+  AND e.account_pubkey = totals.account_pubkey;
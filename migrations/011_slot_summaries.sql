@@ -0,0 +1,29 @@
+-- One row per processed slot with block-wide totals, so contention can be
+-- correlated with how full blocks are. Skipped slots are recorded explicitly.
+CREATE TABLE slot_summaries (
+    time TIMESTAMPTZ NOT NULL,
+    time_estimated BOOLEAN NOT NULL DEFAULT false,
+    ingested_at TIMESTAMPTZ,
+    slot BIGINT NOT NULL,
+    skipped BOOLEAN NOT NULL,
+    parent_slot BIGINT,
+
+    transaction_count INTEGER NOT NULL,
+    vote_transaction_count INTEGER NOT NULL,
+    failed_transaction_count INTEGER NOT NULL,
+
+    total_compute_units BIGINT NOT NULL,
+    total_fees_lamports BIGINT NOT NULL,
+    total_priority_fees_lamports BIGINT NOT NULL,
+    -- total_compute_units against the configured block CU limit (MAX_BLOCK_COMPUTE_UNITS)
+    block_fullness_pct REAL NOT NULL,
+
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+SELECT create_hypertable('slot_summaries', 'time');
+
+CREATE UNIQUE INDEX idx_slot_summary_slot ON slot_summaries (slot, time);
+CREATE INDEX idx_slot_summary_time ON slot_summaries (time DESC);
+
+SELECT add_retention_policy('slot_summaries', INTERVAL '7 days');
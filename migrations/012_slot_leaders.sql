@@ -0,0 +1,5 @@
+-- Leader identity of each slot, from getSlotLeaders. NULL when the node no
+-- longer serves the schedule of the slot's epoch.
+ALTER TABLE slot_summaries ADD COLUMN leader TEXT;
+
+CREATE INDEX idx_slot_summary_leader ON slot_summaries (leader, time DESC);
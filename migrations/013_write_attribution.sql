@@ -0,0 +1,9 @@
+-- Writes are attributed per instruction, inner instructions included.
+-- program_id is now the innermost program referencing the account rather than
+-- the first non-system program of the transaction, and is NULL for accounts no
+-- instruction references (typically the fee payer).
+
+-- Program of the top-level instruction the account was reached from
+ALTER TABLE write_lock_events
+    ADD COLUMN invoking_program_id TEXT,
+    ADD COLUMN instruction_index INTEGER;
@@ -0,0 +1,14 @@
+-- Failed transactions are classified from meta.err and program logs.
+
+-- success is NULL when the RPC returned no status, instead of counting as a failure
+ALTER TABLE write_lock_events ALTER COLUMN success DROP NOT NULL;
+ALTER TABLE read_lock_events ALTER COLUMN success DROP NOT NULL;
+
+-- slippage, stale_oracle, compute_budget_exceeded, insufficient_funds,
+-- custom_error, instruction_error or transaction_error; NULL unless failed
+ALTER TABLE write_lock_events
+    ADD COLUMN failure_reason TEXT,
+    -- Top-level instruction that failed
+    ADD COLUMN error_instruction_index INTEGER,
+    -- InstructionError::Custom code
+    ADD COLUMN error_code BIGINT;
@@ -0,0 +1,8 @@
+-- Jito tips are bid separately from ComputeBudget priority fees.
+
+-- Lamports the transaction transferred to Jito tip accounts, inner instructions included
+ALTER TABLE write_lock_events
+    ADD COLUMN tip_lamports BIGINT NOT NULL DEFAULT 0,
+    -- Part of a run of up to 5 transactions ending in a tip, from the first one
+    -- that shares a signer with the tipping transaction
+    ADD COLUMN likely_bundle BOOLEAN NOT NULL DEFAULT false;
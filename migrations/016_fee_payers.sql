@@ -0,0 +1,4 @@
+-- Who sent each transaction: the fee payer, its first signer
+ALTER TABLE write_lock_events ADD COLUMN fee_payer TEXT;
+
+CREATE INDEX idx_fee_payer_time ON write_lock_events (fee_payer, time DESC);
@@ -0,0 +1,4 @@
+-- Mint of each written token account, from the transaction's pre/post token balances
+ALTER TABLE write_lock_events ADD COLUMN mint TEXT;
+
+CREATE INDEX idx_mint_time ON write_lock_events (mint, time DESC) WHERE mint IS NOT NULL;
@@ -0,0 +1,9 @@
+-- Filled in by the indexer's account enricher from getMultipleAccounts.
+-- program_id holds the account's owner program.
+ALTER TABLE account_metadata
+    ADD COLUMN data_len BIGINT,
+    ADD COLUMN executable BOOLEAN,
+    ADD COLUMN account_type TEXT,
+    ADD COLUMN enriched_at TIMESTAMPTZ;
+
+CREATE INDEX idx_account_metadata_type ON account_metadata (account_type);
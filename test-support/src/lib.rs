@@ -0,0 +1,358 @@
+//! In-process mock of a Solana RPC node, so indexer tests run without network access.
+//!
+//! `MockRpcServer` serves JSON-RPC (`getSlot`, `getBlock`, `getBlocks`, `getBlockTime`,
+//! `getSlotLeaders`, `getEpochSchedule`, `getLeaderSchedule`, `getMultipleAccounts`,
+//! `getVersion`) over HTTP and `slotSubscribe`/`blockSubscribe` over websocket on the
+//! same port, from a `MockChain` built in the test or loaded from recorded block files.
+//! Faults (429s, slow responses, server errors) can be injected per method.
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs;
+use std::io;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// Leader reported for slots without an explicit one
+pub const DEFAULT_LEADER: &str = "68qTstPmwfaDbt1a9hnq7XeyZ4zJBqBKU1esRtQXnKWj";
+
+/// Widest slot range `getBlocks` serves, as on a real node
+const MAX_GET_BLOCKS_RANGE: u64 = 500_000;
+
+/// Mainnet epoch length, used unless a test sets its own
+const DEFAULT_SLOTS_PER_EPOCH: u64 = 432_000;
+
+// Error codes of the Solana JSON-RPC server
+const BLOCK_NOT_AVAILABLE: i64 = -32004;
+const SLOT_SKIPPED: i64 = -32007;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+/// Chain state served by the mock node
+#[derive(Debug, Clone, Default)]
+pub struct MockChain {
+    /// `getBlock` results, `None` for skipped slots
+    blocks: BTreeMap<u64, Option<Value>>,
+    leaders: BTreeMap<u64, String>,
+    slots_per_epoch: Option<u64>,
+    /// `getMultipleAccounts` entries by pubkey
+    accounts: HashMap<String, Value>,
+    tip: Option<u64>,
+    /// Pushed to every subscriber after the subscription is acknowledged
+    notifications: Vec<Value>,
+}
+
+impl MockChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load `<slot>.json` files holding `getBlock` results (`null` for skipped slots)
+    pub fn from_fixture_dir(dir: &Path) -> io::Result<Self> {
+        let mut chain = Self::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let slot = path.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix(".json"))
+                .and_then(|stem| stem.parse::<u64>().ok());
+            if let Some(slot) = slot {
+                let block: Value = serde_json::from_slice(&fs::read(&path)?)?;
+                chain.blocks.insert(slot, Some(block).filter(|block| !block.is_null()));
+            }
+        }
+        Ok(chain)
+    }
+
+    pub fn with_block(mut self, slot: u64, block: Value) -> Self {
+        self.blocks.insert(slot, Some(block));
+        self
+    }
+
+    /// Mark a slot as skipped: `getBlocks` omits it and `getBlock` returns the skipped error
+    pub fn with_skipped(mut self, slot: u64) -> Self {
+        self.blocks.insert(slot, None);
+        self
+    }
+
+    pub fn with_leader(mut self, slot: u64, leader: &str) -> Self {
+        self.leaders.insert(slot, leader.to_string());
+        self
+    }
+
+    /// Epoch length reported by `getEpochSchedule`, without warmup
+    pub fn with_slots_per_epoch(mut self, slots: u64) -> Self {
+        self.slots_per_epoch = Some(slots);
+        self
+    }
+
+    /// An account holding `space` zero bytes; unknown accounts are returned as `null`
+    pub fn with_account(mut self, pubkey: &str, owner: &str, space: u64, executable: bool) -> Self {
+        // Base64 of zero bytes, without pulling in an encoder
+        let padding = ["", "AA==", "AAA="][(space % 3) as usize];
+        let data = "AAAA".repeat((space / 3) as usize) + padding;
+        self.accounts.insert(pubkey.to_string(), json!({
+            "lamports": 1_000_000,
+            "data": [data, "base64"],
+            "owner": owner,
+            "executable": executable,
+            "rentEpoch": 0,
+            "space": space,
+        }));
+        self
+    }
+
+    /// Confirmed tip reported by `getSlot` (defaults to the highest known slot)
+    pub fn with_tip(mut self, slot: u64) -> Self {
+        self.tip = Some(slot);
+        self
+    }
+
+    /// Notification results sent to each subscriber, after which the socket is closed
+    pub fn with_notifications(mut self, notifications: Vec<Value>) -> Self {
+        self.notifications = notifications;
+        self
+    }
+
+    fn slots_per_epoch(&self) -> u64 {
+        self.slots_per_epoch.unwrap_or(DEFAULT_SLOTS_PER_EPOCH)
+    }
+
+    fn tip(&self) -> u64 {
+        self.tip.or_else(|| self.blocks.keys().next_back().copied()).unwrap_or(0)
+    }
+}
+
+/// A failure returned instead of the normal response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// HTTP 429 with `Retry-After: 0`
+    RateLimited,
+    /// Respond normally, but only after the delay
+    Slow(Duration),
+    /// JSON-RPC internal error
+    ServerError,
+}
+
+#[derive(Default)]
+struct MockState {
+    chain: MockChain,
+    /// Pending faults per method, consumed one per call
+    faults: HashMap<String, VecDeque<Fault>>,
+    calls: HashMap<String, usize>,
+}
+
+type SharedState = Arc<Mutex<MockState>>;
+
+/// A mock RPC node listening on a local port. Stops when dropped.
+pub struct MockRpcServer {
+    addr: SocketAddr,
+    state: SharedState,
+    task: JoinHandle<()>,
+}
+
+impl MockRpcServer {
+    pub async fn start(chain: MockChain) -> Self {
+        let state: SharedState = Arc::new(Mutex::new(MockState { chain, ..Default::default() }));
+        let app = Router::new()
+            .route("/", get(pubsub).post(rpc))
+            .with_state(state.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await
+            .expect("failed to bind mock RPC server");
+        let addr = listener.local_addr().expect("mock RPC server has no address");
+        let task = tokio::spawn(async move {
+            axum::serve(listener, app).await.expect("mock RPC server failed");
+        });
+
+        Self { addr, state, task }
+    }
+
+    /// JSON-RPC endpoint
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Pubsub endpoint
+    pub fn ws_url(&self) -> String {
+        format!("ws://{}", self.addr)
+    }
+
+    /// Move the confirmed tip, e.g. to release more fixture blocks
+    pub fn set_tip(&self, slot: u64) {
+        self.state.lock().unwrap().chain.tip = Some(slot);
+    }
+
+    /// Answer the next `times` calls of `method` with `fault`
+    pub fn inject(&self, method: &str, fault: Fault, times: usize) {
+        self.state.lock().unwrap()
+            .faults
+            .entry(method.to_string())
+            .or_default()
+            .extend(std::iter::repeat_n(fault, times));
+    }
+
+    /// Number of calls of `method` received so far, faulted ones included
+    pub fn calls(&self, method: &str) -> usize {
+        self.state.lock().unwrap().calls.get(method).copied().unwrap_or(0)
+    }
+}
+
+impl Drop for MockRpcServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn rpc(State(state): State<SharedState>, Json(request): Json<Value>) -> Response {
+    let method = request["method"].as_str().unwrap_or_default().to_string();
+    let id = request["id"].clone();
+
+    let fault = {
+        let mut state = state.lock().unwrap();
+        *state.calls.entry(method.clone()).or_default() += 1;
+        state.faults.get_mut(&method).and_then(|faults| faults.pop_front())
+    };
+
+    match fault {
+        Some(Fault::RateLimited) => {
+            return (StatusCode::TOO_MANY_REQUESTS, [(header::RETRY_AFTER, "0")]).into_response();
+        }
+        Some(Fault::ServerError) => {
+            return rpc_error(id, INTERNAL_ERROR, "Internal error".to_string());
+        }
+        Some(Fault::Slow(delay)) => tokio::time::sleep(delay).await,
+        None => {}
+    }
+
+    let result = {
+        let state = state.lock().unwrap();
+        handle(&state.chain, &method, &request["params"])
+    };
+    match result {
+        Ok(result) => Json(json!({ "jsonrpc": "2.0", "id": id, "result": result })).into_response(),
+        Err((code, message)) => rpc_error(id, code, message),
+    }
+}
+
+fn rpc_error(id: Value, code: i64, message: String) -> Response {
+    Json(json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })).into_response()
+}
+
+fn handle(chain: &MockChain, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+    let tip = chain.tip();
+    let slot_param = |index: usize| params[index].as_u64().unwrap_or(0);
+
+    match method {
+        "getVersion" => Ok(json!({ "solana-core": "1.18.26", "feature-set": 0 })),
+        "getSlot" => Ok(json!(tip)),
+        "getBlocks" => {
+            let start = slot_param(0);
+            let end = params[1].as_u64().unwrap_or(tip).min(tip);
+            if end.saturating_sub(start) > MAX_GET_BLOCKS_RANGE {
+                return Err((INVALID_PARAMS, format!("Slot range too large; max {}", MAX_GET_BLOCKS_RANGE)));
+            }
+            let produced: Vec<u64> = chain.blocks.range(start..=end.max(start))
+                .filter(|(slot, block)| **slot <= end && block.is_some())
+                .map(|(slot, _)| *slot)
+                .collect();
+            Ok(json!(produced))
+        }
+        "getBlock" => {
+            let slot = slot_param(0);
+            match chain.blocks.get(&slot) {
+                Some(Some(block)) if slot <= tip => Ok(block.clone()),
+                Some(None) if slot <= tip => Err((
+                    SLOT_SKIPPED,
+                    format!("Slot {} was skipped, or missing due to ledger jump to recent snapshot", slot),
+                )),
+                _ => Err((BLOCK_NOT_AVAILABLE, format!("Block not available for slot {}", slot))),
+            }
+        }
+        "getBlockTime" => {
+            let slot = slot_param(0);
+            match chain.blocks.get(&slot) {
+                Some(Some(block)) => Ok(block["blockTime"].clone()),
+                _ => Err((BLOCK_NOT_AVAILABLE, format!("Block not available for slot {}", slot))),
+            }
+        }
+        "getSlotLeaders" => {
+            let start = slot_param(0);
+            let limit = slot_param(1);
+            let leaders: Vec<&str> = (start..start + limit)
+                .map(|slot| chain.leaders.get(&slot).map(String::as_str).unwrap_or(DEFAULT_LEADER))
+                .collect();
+            Ok(json!(leaders))
+        }
+        "getEpochSchedule" => {
+            let slots_per_epoch = chain.slots_per_epoch();
+            Ok(json!({
+                "slotsPerEpoch": slots_per_epoch,
+                "leaderScheduleSlotOffset": slots_per_epoch,
+                "warmup": false,
+                "firstNormalEpoch": 0,
+                "firstNormalSlot": 0,
+            }))
+        }
+        "getLeaderSchedule" => {
+            // Like a real node, only the schedule of the tip's epoch is available
+            let slots_per_epoch = chain.slots_per_epoch();
+            let epoch = params[0].as_u64().unwrap_or(tip) / slots_per_epoch;
+            if epoch != tip / slots_per_epoch {
+                return Ok(Value::Null);
+            }
+            let first_slot = epoch * slots_per_epoch;
+            let mut schedule: HashMap<&str, Vec<u64>> = HashMap::new();
+            for index in 0..slots_per_epoch {
+                let leader = chain.leaders.get(&(first_slot + index)).map(String::as_str).unwrap_or(DEFAULT_LEADER);
+                schedule.entry(leader).or_default().push(index);
+            }
+            Ok(json!(schedule))
+        }
+        "getMultipleAccounts" => {
+            let accounts: Vec<Value> = params[0].as_array().into_iter().flatten()
+                .map(|pubkey| pubkey.as_str().and_then(|p| chain.accounts.get(p)).cloned().unwrap_or(Value::Null))
+                .collect();
+            Ok(json!({ "context": { "slot": tip }, "value": accounts }))
+        }
+        other => Err((-32601, format!("Method not found: {}", other))),
+    }
+}
+
+async fn pubsub(State(state): State<SharedState>, ws: WebSocketUpgrade) -> Response {
+    let notifications = state.lock().unwrap().chain.notifications.clone();
+    ws.on_upgrade(move |socket| serve_subscription(socket, notifications))
+}
+
+/// Acknowledge the first subscription, push the notifications and close the socket
+async fn serve_subscription(mut socket: WebSocket, notifications: Vec<Value>) {
+    let Some(Ok(Message::Text(request))) = socket.recv().await else { return };
+    let Ok(request) = serde_json::from_str::<Value>(&request) else { return };
+    let method = request["method"].as_str().unwrap_or_default().replace("Subscribe", "Notification");
+
+    let ack = json!({ "jsonrpc": "2.0", "id": request["id"], "result": 1 });
+    if socket.send(Message::Text(ack.to_string())).await.is_err() {
+        return;
+    }
+
+    for result in notifications {
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": { "result": result, "subscription": 1 },
+        });
+        if socket.send(Message::Text(notification.to_string())).await.is_err() {
+            return;
+        }
+    }
+    let _ = socket.close().await;
+}